@@ -0,0 +1,173 @@
+use super::{Algorithm, SortSettings, Traced};
+use std::cmp::Ordering;
+
+//mcilroy's antiqsort: every element starts out as gas, bigger than anything solid,
+//and is only frozen to a value once a comparison can't be answered otherwise
+//the candidate is the gas element most recently compared against a solid one, freezing it
+//first keeps the pivot of a quicksort solid and small, so every partition comes out lopsided
+pub(super) struct Adversary {
+    values: Vec<Option<usize>>, //frozen value of every id, None while it is gas
+    solid: usize,               //elements frozen so far
+    candidate: i32,
+}
+
+impl Adversary {
+    fn new(n: usize) -> Self {
+        Adversary {
+            values: vec![None; n],
+            solid: 0,
+            candidate: 0,
+        }
+    }
+
+    fn freeze(&mut self, id: i32) {
+        self.values[id as usize] = Some(self.solid);
+        self.solid += 1;
+    }
+
+    //compares the elements with ids x and y
+    pub(super) fn compare(&mut self, x: i32, y: i32) -> Ordering {
+        let gas = |adversary: &Self, id: i32| adversary.values[id as usize].is_none();
+        if gas(self, x) && gas(self, y) {
+            self.freeze(if x == self.candidate { x } else { y });
+        }
+        if gas(self, x) {
+            self.candidate = x;
+        } else if gas(self, y) {
+            self.candidate = y;
+        }
+        let value = |id: i32| self.values[id as usize].unwrap_or(usize::MAX);
+        value(x).cmp(&value(y))
+    }
+
+    //values 1..=n of the ids, elements that stayed gas are frozen in order
+    fn input(mut self) -> Vec<i32> {
+        for id in 0..self.values.len() {
+            if self.values[id].is_none() {
+                self.freeze(id as i32);
+            }
+        }
+        self.values
+            .iter()
+            .map(|x| x.unwrap_or(0) as i32 + 1)
+            .collect()
+    }
+}
+
+pub fn has_worst_case(algorithm: Algorithm) -> bool {
+    matches!(
+        algorithm,
+        Algorithm::Quick
+            | Algorithm::Intro
+            | Algorithm::Pdq
+            | Algorithm::Insertion
+            | Algorithm::Bubble
+            | Algorithm::Cocktail
+            | Algorithm::Gnome
+            | Algorithm::OddEven
+            | Algorithm::Merge
+            | Algorithm::TopDownMerge
+    )
+}
+
+//list of n elements that makes the algorithm do the most work, None if there is no known one
+pub fn worst_case(algorithm: Algorithm, settings: &SortSettings, n: usize) -> Option<Vec<i32>> {
+    let sorted: Vec<i32> = (1..=n as i32).collect();
+    match algorithm {
+        Algorithm::Quick | Algorithm::Intro | Algorithm::Pdq => {
+            //the sort runs on the ids 0..n, the seed makes a random pivot pick the same later on
+            let mut adversary = Adversary::new(n);
+            let mut ids: Vec<i32> = (0..n as i32).collect();
+            let mut stepper = algorithm.stepper(settings);
+            let mut events = vec![];
+            while !stepper.step(&mut Traced::with_adversary(
+                &mut ids,
+                &mut events,
+                &mut adversary,
+            )) {
+                events.clear();
+            }
+            Some(adversary.input())
+        }
+        Algorithm::Insertion
+        | Algorithm::Bubble
+        | Algorithm::Cocktail
+        | Algorithm::Gnome
+        | Algorithm::OddEven => Some(sorted.into_iter().rev().collect()),
+        //runs of bottom-up merge sort split at the largest power of two below their length
+        Algorithm::Merge => Some(unmerge(sorted, |len| {
+            1 << (usize::BITS - 1 - (len - 1).leading_zeros())
+        })),
+        Algorithm::TopDownMerge => Some(unmerge(sorted, |len| len / 2)),
+        _ => None,
+    }
+}
+
+//arranges sorted values so that every merge compares until both runs are almost empty
+//the largest values alternate between the two runs, which are then arranged the same way
+fn unmerge(sorted: Vec<i32>, split: fn(usize) -> usize) -> Vec<i32> {
+    let len = sorted.len();
+    if len <= 1 {
+        return sorted;
+    }
+    let size = split(len);
+    let (mut left, mut right) = (vec![], vec![]);
+    for (k, x) in sorted.into_iter().rev().enumerate() {
+        if left.len() < size && (k % 2 == 0 || right.len() == len - size) {
+            left.push(x);
+        } else {
+            right.push(x);
+        }
+    }
+    left.reverse();
+    right.reverse();
+    let mut v = unmerge(left, split);
+    v.extend(unmerge(right, split));
+    v
+}
+
+#[test]
+fn worst_case_test() {
+    //checking that the worst cases are permutations and cost more than a shuffled list
+    use super::cost::measure_on;
+    use super::{Partition, PivotRule};
+    use rand::seq::SliceRandom;
+    use rand::thread_rng;
+
+    let n = 300;
+    let quick = SortSettings {
+        partition: Partition::Hoare,
+        pivot: PivotRule::MedianOfThree,
+        ..Default::default()
+    };
+    let cases = [
+        (Algorithm::Quick, SortSettings::default()),
+        (Algorithm::Quick, quick),
+        (Algorithm::Insertion, SortSettings::default()),
+        (Algorithm::Merge, SortSettings::default()),
+        (Algorithm::TopDownMerge, SortSettings::default()),
+    ];
+    for (algorithm, settings) in cases {
+        let worst = worst_case(algorithm, &settings, n).unwrap();
+        let mut sorted = worst.clone();
+        sorted.sort();
+        assert_eq!(sorted, (1..=n as i32).collect::<Vec<_>>());
+        let mut shuffled = sorted;
+        shuffled.shuffle(&mut thread_rng());
+        let worst = measure_on(algorithm, &settings, worst).comparisons;
+        assert!(worst > measure_on(algorithm, &settings, shuffled).comparisons);
+    }
+    //quicksort picking the last element goes quadratic
+    let last = worst_case(Algorithm::Quick, &SortSettings::default(), n).unwrap();
+    let comparisons = measure_on(Algorithm::Quick, &SortSettings::default(), last).comparisons;
+    assert!(comparisons > n * n / 4);
+    //every merge of the worst case only stops one element early, 4 merges of 2, 2 of 4 and 1 of 8
+    let merge = worst_case(Algorithm::TopDownMerge, &SortSettings::default(), 8).unwrap();
+    let comparisons =
+        measure_on(Algorithm::TopDownMerge, &SortSettings::default(), merge).comparisons;
+    assert_eq!(comparisons, 4 + 2 * 3 + 7);
+    for algorithm in Algorithm::ALL {
+        let worst = worst_case(algorithm, &SortSettings::default(), 10);
+        assert_eq!(worst.is_some(), has_worst_case(algorithm));
+    }
+}
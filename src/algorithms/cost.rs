@@ -0,0 +1,140 @@
+use super::{Algorithm, Counters, SortSettings, Traced};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+
+//work done by one run of the algorithm on a shuffled list of 1..=n, counted without recording a trace
+//the list is shuffled from the seed, so every algorithm measures the same list and a chart can be reproduced
+pub fn measure(algorithm: Algorithm, settings: &SortSettings, n: usize) -> Counters {
+    let mut v: Vec<i32> = (1..=n as i32).collect();
+    v.shuffle(&mut StdRng::seed_from_u64(settings.seed));
+    measure_on(algorithm, settings, v)
+}
+
+//work done by one run of the algorithm on the given list
+pub(super) fn measure_on(
+    algorithm: Algorithm,
+    settings: &SortSettings,
+    mut v: Vec<i32>,
+) -> Counters {
+    let mut stepper = algorithm.stepper(settings);
+    let mut counters = Counters::default();
+    let mut events = vec![];
+    loop {
+        let done = stepper.step(&mut Traced::new(&mut v, &mut events));
+        for event in events.drain(..) {
+            counters.add(event);
+        }
+        if done {
+            return counters;
+        }
+    }
+}
+
+//what the cost chart plots against n
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Metric {
+    Comparisons,
+    Swaps,
+}
+
+impl Metric {
+    pub const ALL: [Metric; 2] = [Metric::Comparisons, Metric::Swaps];
+
+    pub fn of(self, counters: &Counters) -> usize {
+        match self {
+            Metric::Comparisons => counters.comparisons,
+            Metric::Swaps => counters.swaps,
+        }
+    }
+}
+
+//costs of the algorithms over a range of list sizes, measured one point at a time
+#[derive(Default)]
+pub struct CostChart {
+    pub curves: Vec<(Algorithm, Vec<(usize, Counters)>)>,
+    pending: Vec<(Algorithm, usize)>, //points still to measure, the last one is next
+    settings: Option<SortSettings>,
+}
+
+impl CostChart {
+    //number of list sizes measured per algorithm
+    pub const POINTS: usize = 10;
+
+    //drops the old curves and queues n/10, 2n/10, ..., n for every algorithm
+    pub fn start(&mut self, algorithms: &[Algorithm], settings: SortSettings, n: usize) {
+        self.curves = algorithms.iter().map(|&a| (a, vec![])).collect();
+        self.pending = vec![];
+        for &algorithm in algorithms.iter().rev() {
+            for point in (1..=Self::POINTS).rev() {
+                self.pending
+                    .push((algorithm, (n * point / Self::POINTS).max(1)));
+            }
+        }
+        self.settings = Some(settings);
+    }
+
+    //measures the next point, returns false once every point is measured
+    pub fn step(&mut self) -> bool {
+        let (Some((algorithm, n)), Some(settings)) = (self.pending.pop(), self.settings) else {
+            return false;
+        };
+        let counters = measure(algorithm, &settings, n);
+        if let Some((_, points)) = self.curves.iter_mut().find(|(a, _)| *a == algorithm) {
+            points.push((n, counters));
+        }
+        true
+    }
+
+    pub fn measuring(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    //fraction of the points measured so far
+    pub fn progress(&self) -> f32 {
+        let total = self.curves.len() * Self::POINTS;
+        if total == 0 {
+            1.
+        } else {
+            1. - self.pending.len() as f32 / total as f32
+        }
+    }
+}
+
+#[test]
+fn cost_test() {
+    //checking the measured costs of insertion sort against the worst case and of merge sort against n log n
+    //and that measuring is seeded
+    for n in [1, 10, 100, 500] {
+        let settings = SortSettings::default();
+        let insertion = measure(Algorithm::Insertion, &settings, n);
+        assert!(insertion.comparisons <= n * n);
+        assert!(insertion.swaps <= n * (n - 1) / 2);
+
+        let merge = measure(Algorithm::Merge, &settings, n);
+        let bound = n * (usize::BITS - n.leading_zeros()) as usize;
+        assert!(merge.comparisons <= bound);
+        assert_eq!(merge.swaps, 0);
+    }
+
+    //the same seed measures the same list
+    let seeded = SortSettings {
+        seed: 5,
+        ..Default::default()
+    };
+    let first = measure(Algorithm::Quick, &seeded, 200);
+    assert_eq!(measure(Algorithm::Quick, &seeded, 200), first);
+
+    let mut chart = CostChart::default();
+    chart.start(
+        &[Algorithm::Quick, Algorithm::Heap],
+        SortSettings::default(),
+        50,
+    );
+    while chart.step() {}
+    assert!(!chart.measuring());
+    for (_, points) in &chart.curves {
+        let sizes: Vec<usize> = points.iter().map(|(n, _)| *n).collect();
+        assert_eq!(sizes, vec![5, 10, 15, 20, 25, 30, 35, 40, 45, 50]);
+    }
+}
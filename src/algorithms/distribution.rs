@@ -0,0 +1,416 @@
+use super::records::record_key;
+use super::{InsertionSort, SortStepper, Traced};
+use bevy::prelude::Color;
+
+//smallest and largest value of the list, (0, 0) when it is empty
+//records are bucketed and counted by their keys
+fn bounds(v: &mut Traced) -> (i32, i32) {
+    let values: Vec<i32> = v.read_all().into_iter().map(|x| v.key(x)).collect();
+    let min = values.iter().min().copied().unwrap_or(0);
+    let max = values.iter().max().copied().unwrap_or(0);
+    (min, max)
+}
+
+//most counts or holes counting and pigeonhole sort may use, a wider range of values
+//would allocate gigabytes and give a histogram too wide to draw
+pub const MAX_SLOTS: usize = 10_000;
+
+//counts or holes needed for the list, one for every value from its smallest to its largest key
+pub fn slots(v: &[i32], records: bool) -> usize {
+    let keys = v.iter().map(|&x| if records { record_key(x) } else { x });
+    let (min, max) = (keys.clone().min(), keys.max());
+    match (min, max) {
+        (Some(min), Some(max)) => (max as i64 - min as i64 + 1) as usize,
+        _ => 0,
+    }
+}
+
+//moves every element of the list into a bucket and then writes the buckets back in order
+//one element is moved per operation
+#[derive(Default, Clone)]
+struct Distribution {
+    buckets: Vec<Vec<i32>>,
+    read: usize,   //next element to distribute
+    write: usize,  //next position to collect into
+    bucket: usize, //bucket being collected
+    taken: usize,  //elements already collected from that bucket
+    collecting: bool,
+}
+
+impl Distribution {
+    fn new(buckets: usize) -> Self {
+        Distribution {
+            buckets: vec![vec![]; buckets],
+            ..Default::default()
+        }
+    }
+
+    //moves one element, returns true once all buckets are collected
+    fn step(&mut self, v: &mut Traced, bucket: impl Fn(i32) -> usize) -> bool {
+        if !self.collecting {
+            if self.read < v.len() {
+                let x = v.take(self.read);
+                self.buckets[bucket(v.key(x))].push(x);
+                self.read += 1;
+                return false;
+            }
+            self.collecting = true;
+        }
+        while self.bucket < self.buckets.len() && self.taken >= self.buckets[self.bucket].len() {
+            self.bucket += 1;
+            self.taken = 0;
+        }
+        if self.bucket >= self.buckets.len() {
+            return true;
+        }
+        v.set(self.write, self.buckets[self.bucket][self.taken]);
+        self.taken += 1;
+        self.write += 1;
+        false
+    }
+
+    fn color(&self, i: usize, collected: Color) -> Option<Color> {
+        let ptr = if self.collecting {
+            self.write
+        } else {
+            self.read
+        };
+        if i == ptr {
+            Some(Color::RED)
+        } else if i < ptr && self.collecting {
+            Some(collected)
+        } else if i < ptr {
+            Some(Color::GRAY)
+        } else {
+            None
+        }
+    }
+
+    fn cursor(&self) -> usize {
+        if self.collecting {
+            self.write
+        } else {
+            self.read
+        }
+    }
+
+    //what is left in the buckets, they shrink as they are collected
+    fn remaining(&self) -> Vec<Vec<i32>> {
+        let mut buckets = self.buckets.clone();
+        if self.collecting {
+            for (b, bucket) in buckets.iter_mut().enumerate() {
+                if b < self.bucket {
+                    bucket.clear();
+                } else if b == self.bucket {
+                    bucket.drain(..self.taken);
+                }
+            }
+        }
+        buckets
+    }
+}
+
+//counts every value, turns the counts into the end of every value's range with prefix sums
+//and then places the elements from the back, so equal values keep their order
+#[derive(Default, Clone)]
+pub struct CountingSort {
+    min: i32,
+    counts: Vec<usize>,
+    ends: Vec<usize>,    //end of the range of every value still to be filled
+    input: Vec<i32>,     //copy of the list made while counting
+    placed: Vec<bool>,   //positions written so far
+    last: Option<usize>, //position of the last write
+    read: usize,         //next element to count
+    started: bool,
+}
+
+impl SortStepper for CountingSort {
+    fn step(&mut self, v: &mut Traced) -> bool {
+        if !self.started {
+            self.started = true;
+            let (min, max) = bounds(v);
+            self.min = min;
+            self.counts = vec![0; (max as i64 - min as i64 + 1) as usize];
+            self.placed = vec![false; v.len()];
+        }
+        let min = self.min as i64;
+        let slot = |v: &Traced, x: i32| (v.key(x) as i64 - min) as usize;
+        if self.read < v.len() {
+            let x = v.get(self.read);
+            self.input.push(x);
+            self.counts[slot(v, x)] += 1;
+            v.write_aux();
+            self.read += 1;
+            return false;
+        }
+        if self.ends.is_empty() {
+            self.ends = self
+                .counts
+                .iter()
+                .scan(0, |end, &count| {
+                    *end += count;
+                    Some(*end)
+                })
+                .collect();
+        }
+        let Some(x) = self.input.pop() else {
+            return true;
+        };
+        let value = slot(v, x);
+        self.ends[value] -= 1;
+        self.counts[value] -= 1;
+        let i = self.ends[value];
+        v.set(i, x);
+        v.write_aux();
+        self.placed[i] = true;
+        self.last = Some(i);
+        false
+    }
+
+    fn color(&self, i: usize) -> Option<Color> {
+        let counting = self.read < self.placed.len();
+        if !self.started {
+            None
+        } else if counting && i == self.read {
+            Some(Color::RED)
+        } else if counting && i < self.read {
+            Some(Color::GRAY)
+        } else if self.last == Some(i) {
+            Some(Color::RED)
+        } else if self.placed.get(i) == Some(&true) {
+            Some(Color::GREEN)
+        } else {
+            None
+        }
+    }
+
+    fn cursor(&self) -> usize {
+        self.last.unwrap_or(self.read)
+    }
+
+    fn histogram(&self) -> Vec<usize> {
+        self.counts.clone()
+    }
+}
+
+//puts every element into the hole of its value and then collects the holes in order
+#[derive(Default, Clone)]
+pub struct PigeonholeSort {
+    min: i32,
+    holes: Distribution,
+    started: bool,
+}
+
+impl SortStepper for PigeonholeSort {
+    fn step(&mut self, v: &mut Traced) -> bool {
+        if !self.started {
+            self.started = true;
+            let (min, max) = bounds(v);
+            self.min = min;
+            self.holes = Distribution::new((max as i64 - min as i64 + 1) as usize);
+        }
+        let min = self.min as i64;
+        self.holes.step(v, |x| (x as i64 - min) as usize)
+    }
+
+    fn color(&self, i: usize) -> Option<Color> {
+        if self.started {
+            self.holes.color(i, Color::GREEN)
+        } else {
+            None
+        }
+    }
+
+    fn cursor(&self) -> usize {
+        self.holes.cursor()
+    }
+
+    fn buckets(&self) -> Vec<Vec<i32>> {
+        self.holes.remaining()
+    }
+}
+
+//distributes the elements into buckets of equal value ranges, collects them
+//and then sorts every bucket in place with insertion sort
+#[derive(Clone)]
+pub struct BucketSort {
+    count: usize,
+    min: i64,
+    range: i64,
+    buckets: Distribution,
+    bounds: Vec<(usize, usize)>, //where each bucket ended up in the list
+    bucket: usize,               //bucket being sorted
+    insertion: InsertionSort,
+    started: bool,
+}
+
+impl BucketSort {
+    pub fn new(count: usize) -> Self {
+        BucketSort {
+            count: count.max(1),
+            min: 0,
+            range: 1,
+            buckets: Distribution::default(),
+            bounds: vec![],
+            bucket: 0,
+            insertion: InsertionSort::default(),
+            started: false,
+        }
+    }
+
+    fn sorting(&self) -> bool {
+        !self.bounds.is_empty()
+    }
+}
+
+impl SortStepper for BucketSort {
+    fn step(&mut self, v: &mut Traced) -> bool {
+        if !self.started {
+            self.started = true;
+            let (min, max) = bounds(v);
+            self.min = min as i64;
+            self.range = max as i64 - min as i64 + 1;
+            self.buckets = Distribution::new(self.count);
+        }
+        if !self.sorting() {
+            let (min, range, count) = (self.min, self.range, self.count as i64);
+            if !self
+                .buckets
+                .step(v, |x| ((x as i64 - min) * count / range) as usize)
+            {
+                return false;
+            }
+            let mut start = 0;
+            for bucket in &self.buckets.buckets {
+                self.bounds.push((start, start + bucket.len()));
+                start += bucket.len();
+            }
+        }
+        while let Some(&(lo, hi)) = self.bounds.get(self.bucket) {
+            if !self.insertion.step(&mut v.slice(lo, hi)) {
+                return false;
+            }
+            self.bucket += 1;
+            self.insertion = InsertionSort::default();
+        }
+        true
+    }
+
+    fn color(&self, i: usize) -> Option<Color> {
+        if !self.started {
+            return None;
+        }
+        if !self.sorting() {
+            return self.buckets.color(i, Color::BLUE);
+        }
+        match self.bounds.get(self.bucket) {
+            Some(&(lo, hi)) if i >= lo && i < hi => self.insertion.color(i - lo),
+            Some(&(lo, _)) if i < lo => Some(Color::GREEN),
+            None => Some(Color::GREEN),
+            _ => None,
+        }
+    }
+
+    fn cursor(&self) -> usize {
+        match self.bounds.get(self.bucket) {
+            Some(&(lo, _)) => lo + self.insertion.cursor(),
+            None => self.buckets.cursor(),
+        }
+    }
+
+    fn buckets(&self) -> Vec<Vec<i32>> {
+        if self.sorting() {
+            vec![]
+        } else {
+            self.buckets.remaining()
+        }
+    }
+
+    fn status(&self) -> Option<String> {
+        if self.sorting() {
+            Some(format!(
+                "Sorting bucket {} of {}",
+                (self.bucket + 1).min(self.count),
+                self.count
+            ))
+        } else {
+            Some(format!("Distributing into {} buckets", self.count))
+        }
+    }
+}
+
+#[test]
+fn counting_sort_test() {
+    //checking if counting sort is correct for one random vec for each len 1-1000
+    use super::{is_sorted, run_to_end};
+    use rand::{thread_rng, Rng};
+
+    for len in 1..=1000 {
+        let mut v: Vec<i32> = (0..len)
+            .map(|_| thread_rng().gen_range(-len..len))
+            .collect();
+        run_to_end(&mut CountingSort::default(), &mut v);
+        assert!(is_sorted(&v));
+    }
+}
+
+#[test]
+fn counting_sort_records_test() {
+    //checking that counting sort counts records by their keys and keeps equal keys in order
+    use super::records::{pack_records, unpack_records, unstable_pairs};
+
+    let keys = [3, 1, 3, 2, 1, 3, 2];
+    let mut v = pack_records(&keys).unwrap();
+    let mut stepper = CountingSort::default();
+    let mut events = vec![];
+    while !stepper.step(&mut Traced::new(&mut v, &mut events).with_records(true)) {}
+    assert_eq!(unpack_records(&v), vec![1, 1, 2, 2, 3, 3, 3]);
+    assert!(unstable_pairs(&v).is_empty());
+    assert_eq!(stepper.counts.len(), 3);
+}
+
+#[test]
+fn pigeonhole_sort_test() {
+    //checking if pigeonhole sort is correct for one random vec for each len 1-1000
+    use super::{is_sorted, run_to_end};
+    use rand::{thread_rng, Rng};
+
+    for len in 1..=1000 {
+        let mut v: Vec<i32> = (0..len)
+            .map(|_| thread_rng().gen_range(-len..len))
+            .collect();
+        run_to_end(&mut PigeonholeSort::default(), &mut v);
+        assert!(is_sorted(&v));
+    }
+}
+
+#[test]
+fn bucket_sort_test() {
+    //checking if bucket sort is correct for a few bucket counts and one random vec for each len 1-1000
+    use super::{is_sorted, run_to_end};
+    use rand::{thread_rng, Rng};
+
+    for count in [1, 10, 100] {
+        for len in 1..=1000 {
+            let mut v: Vec<i32> = (0..len).map(|_| thread_rng().gen::<i32>()).collect();
+            run_to_end(&mut BucketSort::new(count), &mut v);
+            assert!(is_sorted(&v));
+        }
+    }
+}
+
+#[test]
+fn slots_test() {
+    //checking the slots of a range of values and that counting sort refuses a range too wide to allocate
+    use super::records::pack_records;
+    use super::Algorithm;
+
+    assert_eq!(slots(&[3, -2, 5], false), 8);
+    assert_eq!(slots(&[], false), 0);
+    assert_eq!(slots(&pack_records(&[3, -2, 5]).unwrap(), true), 8);
+    let wide = [0, 2_000_000_000];
+    assert!(Algorithm::Counting.check(&wide, false).is_err());
+    assert!(Algorithm::Pigeonhole.check(&wide, false).is_err());
+    assert!(Algorithm::Quick.check(&wide, false).is_ok());
+    assert!(Algorithm::Counting.check(&[1, 1000], false).is_ok());
+}
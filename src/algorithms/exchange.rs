@@ -0,0 +1,329 @@
+use super::{SortStepper, Traced};
+use bevy::prelude::Color;
+
+//swaps neighbours that are out of order, bubbling the largest value to the end on every pass
+#[derive(Clone)]
+pub struct BubbleSort {
+    early_exit: bool, //stop after a pass without swaps
+    end: usize,       //everything from end is sorted
+    i: usize,         //compares i and i + 1
+    swapped: bool,    //whether the current pass has swapped
+    started: bool,
+}
+
+impl BubbleSort {
+    pub fn new(early_exit: bool) -> Self {
+        BubbleSort {
+            early_exit,
+            end: 0,
+            i: 0,
+            swapped: false,
+            started: false,
+        }
+    }
+}
+
+impl SortStepper for BubbleSort {
+    fn step(&mut self, v: &mut Traced) -> bool {
+        if !self.started {
+            self.started = true;
+            self.end = v.len();
+        }
+        if self.i + 1 >= self.end {
+            self.end = self.end.saturating_sub(1);
+            if self.early_exit && !self.swapped {
+                self.end = 0;
+            }
+            self.i = 0;
+            self.swapped = false;
+        }
+        if self.end <= 1 {
+            self.end = 0;
+            return true;
+        }
+        if v.compare(self.i, self.i + 1).is_gt() {
+            v.swap(self.i, self.i + 1);
+            self.swapped = true;
+        }
+        self.i += 1;
+        false
+    }
+
+    fn color(&self, i: usize) -> Option<Color> {
+        if !self.started {
+            None
+        } else if i >= self.end {
+            Some(Color::GREEN)
+        } else if i == self.i {
+            Some(Color::RED)
+        } else {
+            None
+        }
+    }
+
+    fn cursor(&self) -> usize {
+        self.i
+    }
+}
+
+//bubble sort alternating between passes to the right and passes to the left
+#[derive(Default, Clone)]
+pub struct CocktailShakerSort {
+    lo: usize,       //everything before lo is sorted
+    hi: usize,       //everything after hi is sorted
+    i: usize,        //compares i and i + 1
+    backwards: bool, //direction of the current pass
+    swapped: bool,   //whether the current pass has swapped
+    started: bool,
+}
+
+impl SortStepper for CocktailShakerSort {
+    fn step(&mut self, v: &mut Traced) -> bool {
+        if !self.started {
+            self.started = true;
+            self.hi = v.len().saturating_sub(1);
+        }
+        loop {
+            if self.lo >= self.hi {
+                return true;
+            }
+            if !self.backwards && self.i < self.hi {
+                if v.compare(self.i, self.i + 1).is_gt() {
+                    v.swap(self.i, self.i + 1);
+                    self.swapped = true;
+                }
+                self.i += 1;
+                return false;
+            }
+            if self.backwards && self.i > self.lo {
+                if v.compare(self.i - 1, self.i).is_gt() {
+                    v.swap(self.i - 1, self.i);
+                    self.swapped = true;
+                }
+                self.i -= 1;
+                return false;
+            }
+            //the pass is over, its last value is in place
+            if !self.swapped {
+                self.lo = self.hi;
+                continue;
+            }
+            if self.backwards {
+                self.lo += 1;
+                self.i = self.lo;
+            } else {
+                self.hi -= 1;
+                self.i = self.hi;
+            }
+            self.backwards = !self.backwards;
+            self.swapped = false;
+        }
+    }
+
+    fn color(&self, i: usize) -> Option<Color> {
+        if !self.started {
+            None
+        } else if self.lo >= self.hi || i < self.lo || i > self.hi {
+            Some(Color::GREEN)
+        } else if i == self.i {
+            Some(Color::RED)
+        } else {
+            None
+        }
+    }
+
+    fn cursor(&self) -> usize {
+        self.i
+    }
+}
+
+//compares all odd-even pairs and then all even-odd pairs until a round without swaps
+#[derive(Default, Clone)]
+pub struct OddEvenSort {
+    i: usize,      //compares i and i + 1
+    odd: bool,     //whether the pass compares pairs starting at odd indices
+    swapped: bool, //whether the current round has swapped
+    sorted: bool,
+}
+
+impl SortStepper for OddEvenSort {
+    fn step(&mut self, v: &mut Traced) -> bool {
+        loop {
+            if self.sorted {
+                return true;
+            }
+            if self.i + 1 < v.len() {
+                if v.compare(self.i, self.i + 1).is_gt() {
+                    v.swap(self.i, self.i + 1);
+                    self.swapped = true;
+                }
+                self.i += 2;
+                return false;
+            }
+            //a round is one even and one odd pass
+            if self.odd {
+                self.sorted = !self.swapped;
+                self.swapped = false;
+            }
+            self.odd = !self.odd;
+            self.i = self.odd as usize;
+        }
+    }
+
+    fn color(&self, i: usize) -> Option<Color> {
+        if self.sorted {
+            Some(Color::GREEN)
+        } else if i == self.i {
+            Some(Color::RED)
+        } else if i == self.i + 1 {
+            Some(Color::BLUE)
+        } else {
+            None
+        }
+    }
+
+    fn cursor(&self) -> usize {
+        self.i
+    }
+}
+
+//bubble sort comparing elements a shrinking gap apart, ending in bubble sort passes with gap 1
+#[derive(Clone)]
+pub struct CombSort {
+    shrink: f32,
+    gap: usize,
+    i: usize,      //compares i and i + gap
+    swapped: bool, //whether the current pass has swapped
+    sorted: bool,
+    started: bool,
+}
+
+impl CombSort {
+    pub fn new(shrink: f32) -> Self {
+        CombSort {
+            shrink,
+            gap: 0,
+            i: 0,
+            swapped: false,
+            sorted: false,
+            started: false,
+        }
+    }
+
+    fn shrink_gap(&mut self) {
+        self.gap = ((self.gap as f32 / self.shrink) as usize).max(1);
+    }
+}
+
+impl SortStepper for CombSort {
+    fn step(&mut self, v: &mut Traced) -> bool {
+        if !self.started {
+            self.started = true;
+            self.gap = v.len();
+            self.shrink_gap();
+        }
+        loop {
+            if self.sorted || v.len() < 2 {
+                self.sorted = true;
+                return true;
+            }
+            if self.i + self.gap < v.len() {
+                if v.compare(self.i, self.i + self.gap).is_gt() {
+                    v.swap(self.i, self.i + self.gap);
+                    self.swapped = true;
+                }
+                self.i += 1;
+                return false;
+            }
+            self.sorted = self.gap == 1 && !self.swapped;
+            self.shrink_gap();
+            self.i = 0;
+            self.swapped = false;
+        }
+    }
+
+    fn color(&self, i: usize) -> Option<Color> {
+        if self.sorted {
+            Some(Color::GREEN)
+        } else if i == self.i {
+            Some(Color::RED)
+        } else if self.started && i == self.i + self.gap {
+            Some(Color::BLUE)
+        } else {
+            None
+        }
+    }
+
+    fn cursor(&self) -> usize {
+        self.i
+    }
+
+    fn status(&self) -> Option<String> {
+        Some(format!(
+            "Shrink factor {:.2}, gap {}",
+            self.shrink, self.gap
+        ))
+    }
+}
+
+//walks forward while neighbours are in order and swaps its way back when they are not
+#[derive(Default, Clone)]
+pub struct GnomeSort {
+    pos: usize,      //index of the gnome
+    furthest: usize, //furthest index reached, everything before it is sorted
+}
+
+impl SortStepper for GnomeSort {
+    fn step(&mut self, v: &mut Traced) -> bool {
+        if self.pos >= v.len() {
+            return true;
+        }
+        if self.pos == 0 || v.compare(self.pos - 1, self.pos).is_le() {
+            self.pos += 1;
+        } else {
+            v.swap(self.pos - 1, self.pos);
+            self.pos -= 1;
+        }
+        self.furthest = self.furthest.max(self.pos);
+        false
+    }
+
+    fn color(&self, i: usize) -> Option<Color> {
+        if i == self.pos {
+            Some(Color::RED)
+        } else if i <= self.furthest {
+            Some(Color::GREEN)
+        } else {
+            None
+        }
+    }
+
+    fn cursor(&self) -> usize {
+        self.pos
+    }
+}
+
+#[test]
+fn exchange_sort_test() {
+    //checking if every exchange sort is correct for one random vec for each len 1-500
+    use super::{is_sorted, run_to_end};
+    use rand::{thread_rng, Rng};
+
+    for len in 1..=500 {
+        let v: Vec<i32> = (0..len).map(|_| thread_rng().gen::<i32>()).collect();
+        let steppers: [Box<dyn SortStepper>; 7] = [
+            Box::new(BubbleSort::new(true)),
+            Box::new(BubbleSort::new(false)),
+            Box::<CocktailShakerSort>::default(),
+            Box::<OddEvenSort>::default(),
+            Box::new(CombSort::new(1.3)),
+            Box::new(CombSort::new(3.)),
+            Box::<GnomeSort>::default(),
+        ];
+        for mut stepper in steppers {
+            let mut v = v.clone();
+            run_to_end(stepper.as_mut(), &mut v);
+            assert!(is_sorted(&v));
+        }
+    }
+}
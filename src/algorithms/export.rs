@@ -0,0 +1,299 @@
+use super::{Algorithm, Event, SortSettings, Trace, MAX_ITEMS};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fs;
+use std::path::Path;
+
+//a recorded run as it is saved to a file
+//json keeps it in one document, csv has one line per event so two traces can be diffed
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct SavedRun {
+    pub algorithm: Algorithm,
+    pub settings: SortSettings,
+    pub initial: Vec<i32>,
+    pub operations: Vec<Vec<Event>>,
+}
+
+impl SavedRun {
+    pub fn new(algorithm: Algorithm, settings: SortSettings, trace: &Trace) -> Self {
+        SavedRun {
+            algorithm,
+            settings,
+            initial: trace.initial.clone(),
+            operations: (0..trace.operations())
+                .map(|i| trace.operation(i).to_vec())
+                .collect(),
+        }
+    }
+
+    pub fn trace(self) -> Trace {
+        let mut trace = Trace::from_operations(self.initial, self.operations);
+        trace.records = self.settings.records;
+        trace
+    }
+
+    //writes json or csv depending on the extension of the path
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let contents = if is_csv(path) {
+            self.to_csv()
+        } else {
+            self.to_json()?
+        };
+        fs::write(path, contents).map_err(|e| format!("Could not write {}: {}", path.display(), e))
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let contents = fs::read_to_string(path)
+            .map_err(|e| format!("Could not read {}: {}", path.display(), e))?;
+        if is_csv(path) {
+            Self::from_csv(&contents)
+        } else {
+            Self::from_json(&contents)
+        }
+    }
+
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(self).map_err(|e| e.to_string())
+    }
+
+    pub fn from_json(s: &str) -> Result<Self, String> {
+        serde_json::from_str(s).map_err(|e| e.to_string())
+    }
+
+    //algorithm,Quick
+    //setting,pivot,Last (one line per setting)
+    //initial,3,1,2
+    //operations,2
+    //0,compare,0,1
+    //1,write,2,5,7 (operation, event and its indices and values)
+    //1,writeaux (events outside the list have no fields)
+    pub fn to_csv(&self) -> String {
+        let mut lines = vec![format!("algorithm,{:?}", self.algorithm)];
+        if let Ok(Value::Object(settings)) = serde_json::to_value(self.settings) {
+            for (key, value) in settings {
+                let value = match value {
+                    Value::String(name) => name,
+                    value => value.to_string(),
+                };
+                lines.push(format!("setting,{},{}", key, value));
+            }
+        }
+        let initial: Vec<String> = self.initial.iter().map(i32::to_string).collect();
+        lines.push(format!("initial,{}", initial.join(",")));
+        lines.push(format!("operations,{}", self.operations.len()));
+        for (i, operation) in self.operations.iter().enumerate() {
+            for event in operation {
+                lines.push(match event {
+                    Event::Compare(a, b) => format!("{},compare,{},{}", i, a, b),
+                    Event::Swap(a, b) => format!("{},swap,{},{}", i, a, b),
+                    Event::Write(j, old, new) => format!("{},write,{},{},{}", i, j, old, new),
+                    Event::Read(j) => format!("{},read,{}", i, j),
+                    Event::CompareAux => format!("{},compareaux", i),
+                    Event::WriteAux => format!("{},writeaux", i),
+                });
+            }
+        }
+        lines.join("\n") + "\n"
+    }
+
+    pub fn from_csv(s: &str) -> Result<Self, String> {
+        let mut algorithm = None;
+        let mut settings = Map::new();
+        let mut initial = vec![];
+        let mut operations: Vec<Vec<Event>> = vec![];
+        let mut count = None; //number of operations, they are filled in as their events arrive
+        for (n, line) in s.lines().enumerate() {
+            let error = |what: &str| format!("Line {}: {}", n + 1, what);
+            let fields: Vec<&str> = line.trim().split(',').collect();
+            //fields are parsed as the type they are stored in, so nothing out of range wraps around
+            let field = |i: usize| fields.get(i).ok_or_else(|| error("missing field"));
+            let index = |i: usize| -> Result<usize, String> {
+                field(i)?.parse().map_err(|_| error("not a number"))
+            };
+            let value = |i: usize| -> Result<i32, String> {
+                field(i)?.parse().map_err(|_| error("not a number"))
+            };
+            match fields[0] {
+                "" => {}
+                "algorithm" => {
+                    let name = fields.get(1).ok_or_else(|| error("missing algorithm"))?;
+                    algorithm = Some(
+                        serde_json::from_value(Value::String(name.to_string()))
+                            .map_err(|_| error("unknown algorithm"))?,
+                    );
+                }
+                "setting" => {
+                    let (Some(key), Some(value)) = (fields.get(1), fields.get(2)) else {
+                        return Err(error("setting needs a name and a value"));
+                    };
+                    //numbers and booleans are written as they are, everything else is a name
+                    let value = match serde_json::from_str(value) {
+                        Ok(Value::String(_)) | Err(_) => Value::String(value.to_string()),
+                        Ok(value) => value,
+                    };
+                    settings.insert(key.to_string(), value);
+                }
+                "initial" => {
+                    initial = (1..fields.len())
+                        .filter(|&i| !fields[i].is_empty())
+                        .map(value)
+                        .collect::<Result<_, _>>()?;
+                }
+                "operations" => {
+                    let n: usize = field(1)?.parse().map_err(|_| error("not a count"))?;
+                    if n > Trace::LIMIT {
+                        return Err(error("too many operations"));
+                    }
+                    count = Some(n);
+                }
+                _ => {
+                    let i = index(0)?;
+                    if !count.is_some_and(|count| i < count) {
+                        return Err(error("operation out of range"));
+                    }
+                    if operations.len() <= i {
+                        operations.resize_with(i + 1, Vec::new);
+                    }
+                    let operation = &mut operations[i];
+                    let event = match fields.get(1).copied() {
+                        Some("compare") => Event::Compare(index(2)?, index(3)?),
+                        Some("swap") => Event::Swap(index(2)?, index(3)?),
+                        Some("write") => Event::Write(index(2)?, value(3)?, value(4)?),
+                        Some("read") => Event::Read(index(2)?),
+                        Some("compareaux") => Event::CompareAux,
+                        Some("writeaux") => Event::WriteAux,
+                        _ => return Err(error("unknown event")),
+                    };
+                    operation.push(event);
+                }
+            }
+        }
+        let settings = serde_json::from_value(Value::Object(settings))
+            .map_err(|e| format!("Settings: {}", e))?;
+        //operations without events at the end of the run have no lines
+        operations.resize_with(count.unwrap_or(0), Vec::new);
+        Ok(SavedRun {
+            algorithm: algorithm.ok_or("No algorithm in the file")?,
+            settings,
+            initial,
+            operations,
+        })
+    }
+
+    //every index has to be inside the list, or replaying the trace would panic
+    //and the settings have to be ones the sorts can finish with once they are started again
+    pub fn check(&self) -> Result<(), String> {
+        if self.algorithm == Algorithm::Validation {
+            return Err("Validation is not a sorting algorithm".to_string());
+        }
+        if self.initial.is_empty() {
+            return Err("The trace has an empty list".to_string());
+        }
+        if self.initial.len() > MAX_ITEMS {
+            return Err(format!("The trace has more than {} items", MAX_ITEMS));
+        }
+        if self.operations.len() > Trace::LIMIT {
+            return Err(format!(
+                "The trace has more than {} operations",
+                Trace::LIMIT
+            ));
+        }
+        self.settings.check()?;
+        self.algorithm.check(&self.initial, self.settings.records)?;
+        let len = self.initial.len();
+        let inside = |event: &Event| match *event {
+            Event::Compare(a, b) | Event::Swap(a, b) => a < len && b < len,
+            Event::Write(i, ..) | Event::Read(i) => i < len,
+            Event::CompareAux | Event::WriteAux => true,
+        };
+        if self.operations.iter().flatten().all(inside) {
+            Ok(())
+        } else {
+            Err("The trace accesses elements outside of its list".to_string())
+        }
+    }
+}
+
+fn is_csv(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("csv"))
+}
+
+#[test]
+fn export_test() {
+    //checking that a recorded run comes back the same from json and csv
+    use rand::{thread_rng, Rng};
+
+    let settings = SortSettings::default();
+    for algorithm in [Algorithm::Quick, Algorithm::Radix, Algorithm::Merge] {
+        let initial: Vec<i32> = (0..50).map(|_| thread_rng().gen_range(-50..50)).collect();
+        let mut stepper = algorithm.stepper(&settings);
+        let mut trace = Trace::new(initial.clone());
+        let mut v = initial;
+        while !trace.step(stepper.as_mut(), &mut v) {}
+
+        let run = SavedRun::new(algorithm, settings, &trace);
+        assert_eq!(SavedRun::from_json(&run.to_json().unwrap()).unwrap(), run);
+        assert_eq!(SavedRun::from_csv(&run.to_csv()).unwrap(), run);
+        assert!(run.check().is_ok());
+    }
+}
+
+#[test]
+fn check_test() {
+    //checking that runs with settings the controller does not offer or too many items are rejected
+    let run = |settings: SortSettings, initial: Vec<i32>| SavedRun {
+        algorithm: Algorithm::Radix,
+        settings,
+        initial,
+        operations: vec![],
+    };
+    let default = SortSettings::default();
+    assert!(run(default, vec![2, 1]).check().is_ok());
+    for settings in [
+        SortSettings { base: 0, ..default },
+        SortSettings { base: 1, ..default },
+        SortSettings {
+            shrink: 1.,
+            ..default
+        },
+        SortSettings {
+            shrink: -2.,
+            ..default
+        },
+        SortSettings {
+            buckets: 0,
+            ..default
+        },
+        SortSettings {
+            buckets: 101,
+            ..default
+        },
+    ] {
+        assert!(run(settings, vec![2, 1]).check().is_err());
+    }
+    assert!(run(default, vec![1; MAX_ITEMS + 1]).check().is_err());
+    let wide = SavedRun {
+        algorithm: Algorithm::Counting,
+        ..run(default, vec![0, 2_000_000_000])
+    };
+    assert!(wide.check().is_err());
+
+    //operation counts that are negative or too big to be a trace are errors, not allocations
+    let mut saved = run(default, vec![2, 1]);
+    saved.operations = vec![vec![Event::Swap(0, 1)], vec![], vec![]];
+    let saved = saved.to_csv();
+    let csv = |operations: &str| SavedRun::from_csv(&saved.replace("operations,3", operations));
+    assert!(csv("operations,-1").is_err());
+    assert!(csv(&format!("operations,{}", usize::MAX)).is_err());
+    assert!(csv(&format!("operations,{}", Trace::LIMIT + 1)).is_err());
+    assert_eq!(csv("operations,3").unwrap().operations.len(), 3);
+
+    //numbers that do not fit their field are errors instead of wrapping around
+    let swap = |line: &str| SavedRun::from_csv(&saved.replace("0,swap,0,1", line));
+    assert!(swap("0,swap,-1,1").unwrap_err().contains("not a number"));
+    assert!(swap("0,write,0,1,99999999999").is_err());
+    assert!(swap("0,write,0,1,-7").is_ok());
+    let initial = SavedRun::from_csv(&saved.replace("initial,2,1", "initial,2,99999999999"));
+    assert!(initial.is_err());
+}
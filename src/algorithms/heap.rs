@@ -0,0 +1,109 @@
+use super::{SortStepper, Traced};
+use bevy::prelude::Color;
+
+//heap sort on a max-heap stored at the start of the list
+//every operation is one step of a sift-down: comparing a node to its children and swapping with the larger
+#[derive(Default, Clone)]
+pub struct HeapSort {
+    end: usize,       //size of the heap, everything after it is sorted
+    build: usize,     //next node to sift while building the heap, counting down from the middle
+    node: usize,      //node being sifted
+    child: usize,     //child the node was last compared to
+    path: Vec<usize>, //nodes visited by the current sift
+    sifting: bool,
+    built: bool,
+    started: bool,
+}
+
+impl HeapSort {
+    //starts a new sift-down from the given node
+    fn sift(&mut self, node: usize) {
+        self.node = node;
+        self.child = node;
+        self.path = vec![node];
+        self.sifting = true;
+    }
+}
+
+impl SortStepper for HeapSort {
+    fn step(&mut self, v: &mut Traced) -> bool {
+        if !self.started {
+            self.started = true;
+            self.end = v.len();
+            self.build = v.len() / 2;
+        }
+        if !self.sifting {
+            if !self.built {
+                if self.build > 0 {
+                    self.build -= 1;
+                    self.sift(self.build);
+                    return false;
+                }
+                self.built = true;
+            }
+            if self.end <= 1 {
+                return true;
+            }
+            //move the largest element behind the heap and restore the heap property
+            self.end -= 1;
+            v.swap(0, self.end);
+            self.sift(0);
+            return false;
+        }
+        let left = 2 * self.node + 1;
+        if left >= self.end {
+            self.sifting = false;
+            return false;
+        }
+        let mut largest = left;
+        if left + 1 < self.end && v.compare(left + 1, left).is_gt() {
+            largest = left + 1;
+        }
+        self.child = largest;
+        if v.compare(largest, self.node).is_gt() {
+            v.swap(largest, self.node);
+            self.node = largest;
+            self.path.push(largest);
+        } else {
+            self.sifting = false;
+        }
+        false
+    }
+
+    fn color(&self, i: usize) -> Option<Color> {
+        if !self.started {
+            None
+        } else if i >= self.end {
+            Some(Color::GREEN)
+        } else if self.sifting && i == self.node {
+            Some(Color::RED)
+        } else if self.sifting && i == self.child {
+            Some(Color::BLUE)
+        } else if self.sifting && self.path.contains(&i) {
+            Some(Color::ORANGE)
+        } else {
+            None
+        }
+    }
+
+    fn cursor(&self) -> usize {
+        self.node
+    }
+
+    fn heap_size(&self) -> Option<usize> {
+        Some(self.end)
+    }
+}
+
+#[test]
+fn heap_sort_test() {
+    //checking if heap sort is correct for one random vec for each len 1-1000
+    use super::{is_sorted, run_to_end};
+    use rand::{thread_rng, Rng};
+
+    for len in 1..=1000 {
+        let mut v: Vec<i32> = (0..len).map(|_| thread_rng().gen::<i32>()).collect();
+        run_to_end(&mut HeapSort::default(), &mut v);
+        assert!(is_sorted(&v));
+    }
+}
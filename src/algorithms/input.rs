@@ -0,0 +1,161 @@
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::f64::consts::PI;
+
+//shape of the list the sorts start from, every value stays in 1..=n so bars fit the window
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Distribution {
+    Shuffled,
+    Sorted,
+    Reversed,
+    NearlySorted, //sorted with k random swaps
+    RandomTail,   //sorted with the last tenth shuffled
+    Sawtooth,
+    OrganPipe,
+    FewUnique,
+    AllEqual,
+    Gaussian,
+    Duplicates, //every value drawn independently, so some repeat
+}
+
+impl Distribution {
+    pub const ALL: [Distribution; 11] = [
+        Distribution::Shuffled,
+        Distribution::Sorted,
+        Distribution::Reversed,
+        Distribution::NearlySorted,
+        Distribution::RandomTail,
+        Distribution::Sawtooth,
+        Distribution::OrganPipe,
+        Distribution::FewUnique,
+        Distribution::AllEqual,
+        Distribution::Gaussian,
+        Distribution::Duplicates,
+    ];
+
+    //number of teeth of the sawtooth and of values of few unique
+    const PARTS: usize = 5;
+
+    //list of n values, swaps is only used by nearly sorted
+    pub fn generate(self, n: usize, swaps: usize, rng: &mut impl Rng) -> Vec<i32> {
+        let n = n.max(1);
+        let mut v: Vec<i32> = (1..=n as i32).collect();
+        match self {
+            Distribution::Shuffled => v.shuffle(rng),
+            Distribution::Sorted => {}
+            Distribution::Reversed => v.reverse(),
+            Distribution::NearlySorted => {
+                for _ in 0..swaps {
+                    v.swap(rng.gen_range(0..n), rng.gen_range(0..n));
+                }
+            }
+            Distribution::RandomTail => v[n - n / 10..].shuffle(rng),
+            Distribution::Sawtooth => {
+                let tooth = n.div_ceil(Self::PARTS);
+                for (i, x) in v.iter_mut().enumerate() {
+                    *x = ((i % tooth + 1) * n / tooth) as i32;
+                }
+            }
+            Distribution::OrganPipe => {
+                //odd values going up, then even values coming back down
+                for (i, x) in v.iter_mut().enumerate() {
+                    *x = if i < n.div_ceil(2) {
+                        2 * i + 1
+                    } else {
+                        2 * (n - i)
+                    } as i32;
+                }
+            }
+            Distribution::FewUnique => {
+                for x in v.iter_mut() {
+                    *x = (rng.gen_range(1..=Self::PARTS) * n / Self::PARTS).max(1) as i32;
+                }
+            }
+            Distribution::AllEqual => v.fill(n.div_ceil(2) as i32),
+            Distribution::Gaussian => {
+                //box-muller around the middle, clamped to the bar range
+                for x in v.iter_mut() {
+                    let (u1, u2): (f64, f64) = (1. - rng.gen::<f64>(), rng.gen());
+                    let z = (-2. * u1.ln()).sqrt() * (2. * PI * u2).cos();
+                    let value = n as f64 / 2. + z * n as f64 / 6.;
+                    *x = value.round().clamp(1., n as f64) as i32;
+                }
+            }
+            Distribution::Duplicates => {
+                for x in v.iter_mut() {
+                    *x = rng.gen_range(1..=n as i32);
+                }
+            }
+        }
+        v
+    }
+}
+
+//longest list the controller supports
+pub const MAX_ITEMS: usize = 1000;
+
+//list of whole numbers separated by commas or new lines, blank items are skipped
+pub fn parse_list(text: &str) -> Result<Vec<i32>, String> {
+    let items = text
+        .split([',', '\n'])
+        .map(str::trim)
+        .filter(|item| !item.is_empty());
+    let v = items
+        .enumerate()
+        .map(|(i, item)| {
+            item.parse()
+                .map_err(|_| format!("Item {} is not a whole number: {}", i + 1, item))
+        })
+        .collect::<Result<Vec<i32>, _>>()?;
+    if v.is_empty() {
+        Err("The list is empty".to_string())
+    } else if v.len() > MAX_ITEMS {
+        Err(format!(
+            "The list has {} items, at most {} are supported",
+            v.len(),
+            MAX_ITEMS
+        ))
+    } else {
+        Ok(v)
+    }
+}
+
+#[test]
+fn parse_list_test() {
+    //checking that lists split on commas and new lines and that bad input is rejected
+    assert_eq!(parse_list("3, 1,2\n-4\n\n5,"), Ok(vec![3, 1, 2, -4, 5]));
+    assert!(parse_list("1, two, 3").unwrap_err().contains("Item 2"));
+    assert!(parse_list("1.5").is_err());
+    assert!(parse_list(" ,\n").is_err());
+    let long = vec!["1"; MAX_ITEMS + 1].join(",");
+    assert!(parse_list(&long).is_err());
+}
+
+#[test]
+fn distribution_test() {
+    //checking that every distribution has n values in 1..=n, the shapes that can be checked exactly and seeding
+    use rand::rngs::StdRng;
+    use rand::{thread_rng, SeedableRng};
+
+    for n in [1, 2, 7, 100, 1000] {
+        for distribution in Distribution::ALL {
+            let v = distribution.generate(n, 3, &mut thread_rng());
+            assert_eq!(v.len(), n);
+            assert!(v.iter().all(|&x| x >= 1 && x <= n as i32));
+        }
+        let mut organ = Distribution::OrganPipe.generate(n, 0, &mut thread_rng());
+        organ.sort();
+        assert_eq!(organ, (1..=n as i32).collect::<Vec<_>>());
+        let reversed = Distribution::Reversed.generate(n, 0, &mut thread_rng());
+        assert!(reversed.windows(2).all(|w| w[0] > w[1]));
+        let equal = Distribution::AllEqual.generate(n, 0, &mut thread_rng());
+        assert!(equal.windows(2).all(|w| w[0] == w[1]));
+        //the same seed gives the same list
+        let seeded = |seed| StdRng::seed_from_u64(seed);
+        let gaussian = Distribution::Gaussian.generate(n, 0, &mut seeded(3));
+        assert_eq!(
+            gaussian,
+            Distribution::Gaussian.generate(n, 0, &mut seeded(3))
+        );
+    }
+}
@@ -0,0 +1,51 @@
+use super::{SortStepper, Traced};
+use bevy::prelude::Color;
+
+#[derive(Default, Clone)]
+pub struct InsertionSort {
+    main: usize,      //index of main ptr
+    insertion: usize, //index of insertion ptr
+}
+
+impl SortStepper for InsertionSort {
+    fn step(&mut self, v: &mut Traced) -> bool {
+        if self.main >= v.len() {
+            return true;
+        }
+        if self.insertion > 0 && v.compare(self.insertion - 1, self.insertion).is_gt() {
+            v.swap(self.insertion - 1, self.insertion);
+            self.insertion -= 1;
+        } else {
+            self.main += 1;
+            self.insertion = self.main;
+        }
+        false
+    }
+
+    fn color(&self, i: usize) -> Option<Color> {
+        if i == self.insertion {
+            Some(Color::RED)
+        } else if i <= self.main {
+            Some(Color::GREEN)
+        } else {
+            None
+        }
+    }
+
+    fn cursor(&self) -> usize {
+        self.insertion
+    }
+}
+
+#[test]
+fn insertion_sort_test() {
+    //checking if insertion sort is correct for one random vec for each len 1-1000
+    use super::{is_sorted, run_to_end};
+    use rand::{thread_rng, Rng};
+
+    for len in 1..=1000 {
+        let mut v: Vec<i32> = (0..len).map(|_| thread_rng().gen::<i32>()).collect();
+        run_to_end(&mut InsertionSort::default(), &mut v);
+        assert!(is_sorted(&v));
+    }
+}
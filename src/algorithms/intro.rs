@@ -0,0 +1,374 @@
+use super::quick::{Partitioning, Parts};
+use super::{HeapSort, InsertionSort, Partition, PivotRule, SortStepper, Traced};
+use bevy::prelude::Color;
+
+//ranges shorter than this are insertion sorted
+const INTRO_INSERTION: usize = 16;
+const PDQ_INSERTION: usize = 24;
+//most swaps a partial insertion sort may do before pdqsort gives up on it
+const PARTIAL_INSERTION_LIMIT: usize = 8;
+
+//what the hybrid sorts are doing with the range they are working on
+#[derive(Clone)]
+enum Task {
+    Quick(Partitioning),
+    Heap(usize, usize, HeapSort),
+    Insertion(usize, usize, InsertionSort),
+    //insertion sort that gives up after too many swaps, the range still needs sorting then
+    Partial(Range, InsertionSort, usize),
+    //swaps breaking up patterns that made the last partition unbalanced
+    Swaps(Vec<(usize, usize)>, Option<(usize, usize)>),
+}
+
+impl Task {
+    fn mode(&self) -> &'static str {
+        match self {
+            Task::Quick(_) => "quick",
+            Task::Heap(..) => "heap",
+            Task::Insertion(..) => "insertion",
+            Task::Partial(..) => "partial insertion",
+            Task::Swaps(..) => "pattern breaking",
+        }
+    }
+
+    fn color(&self, i: usize) -> Option<Color> {
+        match self {
+            Task::Quick(partitioning) => partitioning.color(i),
+            Task::Heap(lo, hi, heap) if i >= *lo && i < *hi => heap.color(i - lo),
+            Task::Insertion(lo, hi, insertion) if i >= *lo && i < *hi => insertion.color(i - lo),
+            Task::Partial(range, insertion, _) if i >= range.lo && i < range.hi => {
+                insertion.color(i - range.lo)
+            }
+            Task::Swaps(_, Some((a, _))) if i == *a => Some(Color::RED),
+            Task::Swaps(_, Some((_, b))) if i == *b => Some(Color::BLUE),
+            _ => None,
+        }
+    }
+
+    fn cursor(&self) -> usize {
+        match self {
+            Task::Quick(partitioning) => partitioning.cursor(),
+            Task::Heap(lo, _, heap) => lo + heap.cursor(),
+            Task::Insertion(lo, _, insertion) => lo + insertion.cursor(),
+            Task::Partial(range, insertion, _) => range.lo + insertion.cursor(),
+            Task::Swaps(_, last) => last.map_or(0, |(a, _)| a),
+        }
+    }
+}
+
+//quicksort that switches to heap sort once the recursion gets too deep
+//and to insertion sort on short ranges, like the unstable sort of c++
+#[derive(Default, Clone)]
+pub struct IntroSort {
+    stack: Vec<(usize, usize, u32)>, //ranges left to sort and the depth they may still recurse
+    range: Option<(usize, usize, u32)>, //range being partitioned
+    task: Option<Task>,
+    placed: Vec<bool>, //elements known to be in their final position
+    started: bool,
+}
+
+impl IntroSort {
+    fn push(&mut self, (lo, hi): (usize, usize), depth: u32) {
+        if hi > lo {
+            self.stack.push((lo, hi, depth));
+        }
+    }
+}
+
+impl SortStepper for IntroSort {
+    fn step(&mut self, v: &mut Traced) -> bool {
+        if !self.started {
+            self.started = true;
+            self.placed = vec![false; v.len()];
+            //depth limit of 2 log2 n
+            let depth = 2 * (usize::BITS - v.len().leading_zeros());
+            self.push((0, v.len()), depth);
+        }
+        loop {
+            match &mut self.task {
+                None => {
+                    let Some((lo, hi, depth)) = self.stack.pop() else {
+                        return true;
+                    };
+                    self.task = Some(if hi - lo <= INTRO_INSERTION {
+                        Task::Insertion(lo, hi, InsertionSort::default())
+                    } else if depth == 0 {
+                        Task::Heap(lo, hi, HeapSort::default())
+                    } else {
+                        self.range = Some((lo, hi, depth));
+                        //median of three never draws from the seed
+                        let rule = PivotRule::MedianOfThree;
+                        Task::Quick(Partitioning::new(Partition::Hoare, rule, lo, hi, 0))
+                    });
+                }
+                Some(Task::Quick(partitioning)) => {
+                    if let Some(parts) = partitioning.step(v) {
+                        let (_, _, depth) = self.range.take().expect("partitioned range is known");
+                        self.task = None;
+                        self.push(parts.right, depth - 1);
+                        self.push(parts.left, depth - 1);
+                    }
+                    return false;
+                }
+                Some(Task::Heap(lo, hi, heap)) => {
+                    let (lo, hi) = (*lo, *hi);
+                    if !heap.step(&mut v.slice(lo, hi)) {
+                        return false;
+                    }
+                    self.placed[lo..hi].fill(true);
+                    self.task = None;
+                }
+                Some(Task::Insertion(lo, hi, insertion)) => {
+                    let (lo, hi) = (*lo, *hi);
+                    if !insertion.step(&mut v.slice(lo, hi)) {
+                        return false;
+                    }
+                    self.placed[lo..hi].fill(true);
+                    self.task = None;
+                }
+                Some(_) => {
+                    unreachable!("introsort only partitions, heap sorts and insertion sorts")
+                }
+            }
+        }
+    }
+
+    fn color(&self, i: usize) -> Option<Color> {
+        if let Some(color) = self.task.as_ref().and_then(|task| task.color(i)) {
+            Some(color)
+        } else if self.placed.get(i) == Some(&true) {
+            Some(Color::GREEN)
+        } else {
+            None
+        }
+    }
+
+    fn cursor(&self) -> usize {
+        self.task.as_ref().map_or(0, Task::cursor)
+    }
+
+    fn mode(&self) -> Option<&str> {
+        self.task.as_ref().map(Task::mode)
+    }
+}
+
+//a range left to sort by pdqsort
+#[derive(Clone, Copy)]
+struct Range {
+    lo: usize,
+    hi: usize,
+    budget: u32,    //unbalanced partitions left before switching to heap sort
+    leftmost: bool, //whether the range starts the list, otherwise the element before it is a pivot
+    partial: bool,  //whether to try a partial insertion sort first
+}
+
+//pattern-defeating quicksort, the algorithm behind slice::sort_unstable
+//
+//on top of introsort it partitions elements equal to the pivot out of the way,
+//tries a partial insertion sort when a partition did not swap anything
+//and swaps a few elements around after an unbalanced partition before giving up and heap sorting
+#[derive(Default, Clone)]
+pub struct PdqSort {
+    stack: Vec<Range>,
+    task: Option<Task>,
+    range: Option<Range>, //range being partitioned
+    placed: Vec<bool>,    //elements known to be in their final position
+    started: bool,
+}
+
+impl PdqSort {
+    fn push(&mut self, (lo, hi): (usize, usize), budget: u32, leftmost: bool, partial: bool) {
+        if hi > lo + 1 {
+            self.stack.push(Range {
+                lo,
+                hi,
+                budget,
+                leftmost,
+                partial,
+            });
+        } else if hi == lo + 1 {
+            self.placed[lo] = true;
+        }
+    }
+
+    //picks what to do with the next range, returns false once there is nothing left
+    fn next_task(&mut self) -> bool {
+        let Some(range) = self.stack.pop() else {
+            return false;
+        };
+        let (lo, hi) = (range.lo, range.hi);
+        self.task = Some(if hi - lo < PDQ_INSERTION {
+            Task::Insertion(lo, hi, InsertionSort::default())
+        } else if range.partial {
+            Task::Partial(range, InsertionSort::default(), 0)
+        } else if range.budget == 0 {
+            Task::Heap(lo, hi, HeapSort::default())
+        } else {
+            self.range = Some(range);
+            //median of three never draws from the seed
+            let rule = PivotRule::MedianOfThree;
+            Task::Quick(Partitioning::new(Partition::Lomuto, rule, lo, hi, 0))
+        });
+        true
+    }
+
+    fn partitioned(&mut self, parts: Parts, swaps: usize, equal_left: bool) {
+        let range = self.range.take().expect("partitioned range is known");
+        let pivot = parts.pivot.expect("lomuto places the pivot");
+        self.placed[pivot] = true;
+        if equal_left {
+            //everything left of the pivot is equal to it and already in place
+            self.placed[parts.left.0..parts.left.1].fill(true);
+            self.push(parts.right, range.budget, false, false);
+            return;
+        }
+        let size = range.hi - range.lo;
+        let (left, right) = (pivot - range.lo, range.hi - pivot - 1);
+        if left < size / 8 || right < size / 8 {
+            let budget = range.budget - 1;
+            self.push(parts.right, budget, false, false);
+            self.push(parts.left, budget, range.leftmost, false);
+            let mut swaps = vec![];
+            if left >= PDQ_INSERTION {
+                swaps.push((range.lo, range.lo + left / 4));
+                swaps.push((pivot - 1, pivot - left / 4));
+            }
+            if right >= PDQ_INSERTION {
+                swaps.push((pivot + 1, pivot + 1 + right / 4));
+                swaps.push((range.hi - 1, range.hi - right / 4));
+            }
+            if !swaps.is_empty() {
+                self.task = Some(Task::Swaps(swaps, None));
+            }
+        } else {
+            //a partition without swaps hints at mostly sorted input
+            self.push(parts.right, range.budget, false, swaps == 0);
+            self.push(parts.left, range.budget, range.leftmost, swaps == 0);
+        }
+    }
+}
+
+impl SortStepper for PdqSort {
+    fn step(&mut self, v: &mut Traced) -> bool {
+        if !self.started {
+            self.started = true;
+            self.placed = vec![false; v.len()];
+            //number of unbalanced partitions allowed before switching to heap sort
+            let budget = usize::BITS - v.len().leading_zeros();
+            self.push((0, v.len()), budget, true, false);
+        }
+        loop {
+            match &mut self.task {
+                None => {
+                    if !self.next_task() {
+                        return true;
+                    }
+                }
+                Some(Task::Quick(partitioning)) => {
+                    let picking = !partitioning.picked();
+                    let parts = partitioning.step(v);
+                    let range = self.range.expect("partitioned range is known");
+                    //nothing in the range is smaller than the pivot before it, so if the new pivot
+                    //equals that one the elements equal to it can be put aside
+                    if picking
+                        && !range.leftmost
+                        && v.compare(range.lo - 1, partitioning.pivot_index).is_eq()
+                    {
+                        partitioning.equal_left = true;
+                    }
+                    if let Some(parts) = parts {
+                        let (swaps, equal_left) = (partitioning.swaps, partitioning.equal_left);
+                        self.task = None;
+                        self.partitioned(parts, swaps, equal_left);
+                    }
+                    return false;
+                }
+                Some(Task::Heap(lo, hi, heap)) => {
+                    let (lo, hi) = (*lo, *hi);
+                    if !heap.step(&mut v.slice(lo, hi)) {
+                        return false;
+                    }
+                    self.placed[lo..hi].fill(true);
+                    self.task = None;
+                }
+                Some(Task::Insertion(lo, hi, insertion)) => {
+                    let (lo, hi) = (*lo, *hi);
+                    if !insertion.step(&mut v.slice(lo, hi)) {
+                        return false;
+                    }
+                    self.placed[lo..hi].fill(true);
+                    self.task = None;
+                }
+                Some(Task::Partial(range, insertion, swaps)) => {
+                    let range = *range;
+                    let cursor = insertion.cursor();
+                    if insertion.step(&mut v.slice(range.lo, range.hi)) {
+                        self.placed[range.lo..range.hi].fill(true);
+                        self.task = None;
+                        continue;
+                    }
+                    if insertion.cursor() < cursor {
+                        *swaps += 1;
+                    }
+                    if *swaps > PARTIAL_INSERTION_LIMIT {
+                        //too far from sorted, the range is partitioned after all
+                        self.task = None;
+                        self.stack.push(Range {
+                            partial: false,
+                            ..range
+                        });
+                    }
+                    return false;
+                }
+                Some(Task::Swaps(swaps, last)) => match swaps.pop() {
+                    Some((a, b)) => {
+                        v.swap(a, b);
+                        *last = Some((a, b));
+                        return false;
+                    }
+                    None => self.task = None,
+                },
+            }
+        }
+    }
+
+    fn color(&self, i: usize) -> Option<Color> {
+        if let Some(color) = self.task.as_ref().and_then(|task| task.color(i)) {
+            Some(color)
+        } else if self.placed.get(i) == Some(&true) {
+            Some(Color::GREEN)
+        } else {
+            None
+        }
+    }
+
+    fn cursor(&self) -> usize {
+        self.task.as_ref().map_or(0, Task::cursor)
+    }
+
+    fn mode(&self) -> Option<&str> {
+        self.task.as_ref().map(Task::mode)
+    }
+}
+
+#[test]
+fn intro_sort_test() {
+    //checking if introsort and pdqsort are correct for random, sorted, reversed and equal vecs for each len 1-1000
+    use super::{is_sorted, run_to_end};
+    use rand::{thread_rng, Rng};
+
+    for len in 1..=1000 {
+        let random: Vec<i32> = (0..len).map(|_| thread_rng().gen_range(0..len)).collect();
+        let sorted: Vec<i32> = (0..len).collect();
+        let reversed: Vec<i32> = (0..len).rev().collect();
+        let equal = vec![1; len as usize];
+        for v in [random, sorted, reversed, equal] {
+            let mut intro = v.clone();
+            run_to_end(&mut IntroSort::default(), &mut intro);
+            assert!(is_sorted(&intro));
+            let mut pdq = v;
+            run_to_end(&mut PdqSort::default(), &mut pdq);
+            assert!(is_sorted(&pdq));
+        }
+    }
+}
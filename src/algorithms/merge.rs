@@ -0,0 +1,340 @@
+use super::{SortStepper, Traced};
+use bevy::prelude::Color;
+use std::cmp::{min, Ordering};
+
+//merge of the sorted runs lo..mid and mid..hi through copies of both runs, one write per operation
+//equal elements are taken from the left run first, which keeps the merge stable
+#[derive(Clone)]
+pub(super) struct Merging {
+    pub(super) lo: usize,
+    i1: usize,       //ptr in left run
+    i2: usize,       //ptr in right run
+    left: Vec<i32>,  //copy of left run
+    right: Vec<i32>, //copy of right run
+}
+
+impl Merging {
+    pub(super) fn new(v: &mut Traced, lo: usize, mid: usize, hi: usize) -> Self {
+        Merging {
+            lo,
+            i1: 0,
+            i2: 0,
+            left: (lo..mid).map(|i| v.take(i)).collect(),
+            right: (mid..hi).map(|i| v.take(i)).collect(),
+        }
+    }
+
+    //writes the next element, returns true once both runs are merged
+    pub(super) fn step(&mut self, v: &mut Traced) -> bool {
+        let (i1, i2) = (self.i1, self.i2);
+        let i = self.lo + i1 + i2;
+        let left_first = i1 < self.left.len()
+            && (i2 >= self.right.len() || !v.compare_aux(self.left[i1], self.right[i2]).is_gt());
+        if left_first {
+            v.set(i, self.left[i1]);
+            self.i1 += 1;
+            false
+        } else if i2 < self.right.len() {
+            v.set(i, self.right[i2]);
+            self.i2 += 1;
+            false
+        } else {
+            true
+        }
+    }
+
+    //index of the next write
+    pub(super) fn cursor(&self) -> usize {
+        self.lo + self.i1 + self.i2
+    }
+
+    pub(super) fn hi(&self) -> usize {
+        self.lo + self.left.len() + self.right.len()
+    }
+
+    fn memory(&self) -> usize {
+        self.left.len() + self.right.len()
+    }
+}
+
+//bottom-up merge sort, merging runs of width 1, 2, 4, ... until one run covers the list
+#[derive(Clone)]
+pub struct MergeSort {
+    width: usize, //size of merge
+    merge: usize, //merge number
+    merging: Option<Merging>,
+    peak: usize, //most auxiliary elements used at once
+}
+
+impl Default for MergeSort {
+    fn default() -> Self {
+        MergeSort {
+            width: 1,
+            merge: 0,
+            merging: None,
+            peak: 0,
+        }
+    }
+}
+
+impl SortStepper for MergeSort {
+    fn step(&mut self, v: &mut Traced) -> bool {
+        loop {
+            if self.width >= v.len() {
+                return true;
+            }
+            let lo = self.width * self.merge * 2;
+            if lo >= v.len() {
+                self.width *= 2;
+                self.merge = 0;
+                continue;
+            }
+            let (width, len) = (self.width, v.len());
+            let merging = self.merging.get_or_insert_with(|| {
+                Merging::new(v, lo, min(lo + width, len), min(lo + width * 2, len))
+            });
+            self.peak = self.peak.max(merging.memory());
+            if !merging.step(v) {
+                return false;
+            }
+            //both runs are merged, moving on to the next pair is free
+            self.merge += 1;
+            self.merging = None;
+        }
+    }
+
+    fn color(&self, i: usize) -> Option<Color> {
+        let merging = self.merging.as_ref()?;
+        if i >= merging.lo && i < merging.cursor() {
+            Some(Color::GREEN)
+        } else {
+            None
+        }
+    }
+
+    fn cursor(&self) -> usize {
+        self.merging.as_ref().map_or(0, Merging::cursor)
+    }
+
+    fn status(&self) -> Option<String> {
+        let memory = self.merging.as_ref().map_or(0, Merging::memory);
+        Some(format!(
+            "Auxiliary memory: {} elements (peak {})",
+            memory, self.peak
+        ))
+    }
+}
+
+#[derive(Clone)]
+enum Frame {
+    Sort(usize, usize),         //sort lo..hi
+    Merge(usize, usize, usize), //merge lo..mid and mid..hi
+}
+
+//pushes the frames sorting lo..hi, the left half is popped first
+fn split(stack: &mut Vec<Frame>, lo: usize, hi: usize) {
+    if hi - lo > 1 {
+        let mid = lo + (hi - lo) / 2;
+        stack.push(Frame::Merge(lo, mid, hi));
+        stack.push(Frame::Sort(mid, hi));
+        stack.push(Frame::Sort(lo, mid));
+    }
+}
+
+fn depth(stack: &[Frame]) -> usize {
+    stack
+        .iter()
+        .filter(|frame| matches!(frame, Frame::Merge(..)))
+        .count()
+}
+
+//top-down merge sort, the recursion is kept on an explicit stack so it can be paused at any depth
+#[derive(Default, Clone)]
+pub struct TopDownMergeSort {
+    stack: Vec<Frame>,
+    merging: Option<Merging>,
+    peak: usize, //most auxiliary elements used at once
+    started: bool,
+}
+
+impl SortStepper for TopDownMergeSort {
+    fn step(&mut self, v: &mut Traced) -> bool {
+        if !self.started {
+            self.started = true;
+            split(&mut self.stack, 0, v.len());
+        }
+        loop {
+            if let Some(merging) = &mut self.merging {
+                if !merging.step(v) {
+                    return false;
+                }
+                self.merging = None;
+            }
+            match self.stack.pop() {
+                None => return true,
+                Some(Frame::Sort(lo, hi)) => split(&mut self.stack, lo, hi),
+                Some(Frame::Merge(lo, mid, hi)) => {
+                    let merging = Merging::new(v, lo, mid, hi);
+                    self.peak = self.peak.max(merging.memory());
+                    self.merging = Some(merging);
+                }
+            }
+        }
+    }
+
+    fn color(&self, i: usize) -> Option<Color> {
+        let merging = self.merging.as_ref()?;
+        if i >= merging.lo && i < merging.cursor() {
+            Some(Color::GREEN)
+        } else if i >= merging.lo && i < merging.hi() {
+            Some(Color::YELLOW)
+        } else {
+            None
+        }
+    }
+
+    fn cursor(&self) -> usize {
+        self.merging.as_ref().map_or(0, Merging::cursor)
+    }
+
+    fn status(&self) -> Option<String> {
+        let memory = self.merging.as_ref().map_or(0, Merging::memory);
+        Some(format!(
+            "Auxiliary memory: {} elements (peak {}), recursion depth {}",
+            memory,
+            self.peak,
+            depth(&self.stack) + self.merging.is_some() as usize
+        ))
+    }
+}
+
+//top-down merge sort merging without a buffer by rotating blocks into place
+//a rotation is done as three reversals, every swap of a reversal is one operation
+#[derive(Default, Clone)]
+pub struct InPlaceMergeSort {
+    stack: Vec<Frame>,
+    reversals: Vec<(usize, usize)>, //ranges still to reverse for the current rotation, done before any frame
+    range: Option<(usize, usize)>,  //range of the merge being done
+    swap: Option<(usize, usize)>,   //last swapped pair
+    started: bool,
+}
+
+impl SortStepper for InPlaceMergeSort {
+    fn step(&mut self, v: &mut Traced) -> bool {
+        if !self.started {
+            self.started = true;
+            split(&mut self.stack, 0, v.len());
+        }
+        loop {
+            if let Some((lo, hi)) = self.reversals.pop() {
+                if hi - lo >= 2 {
+                    v.swap(lo, hi - 1);
+                    self.swap = Some((lo, hi - 1));
+                    self.reversals.push((lo + 1, hi - 1));
+                    return false;
+                }
+                continue;
+            }
+            match self.stack.pop() {
+                None => {
+                    self.range = None;
+                    self.swap = None;
+                    return true;
+                }
+                Some(Frame::Sort(lo, hi)) => split(&mut self.stack, lo, hi),
+                Some(Frame::Merge(lo, mid, hi)) => {
+                    if lo >= mid || mid >= hi {
+                        continue;
+                    }
+                    self.range = Some((lo, hi));
+                    self.swap = None;
+                    if hi - lo == 2 {
+                        if v.compare(mid, lo).is_lt() {
+                            v.swap(lo, mid);
+                            self.swap = Some((lo, mid));
+                        }
+                        return false;
+                    }
+                    //split the longer run in half and find where its middle goes in the other run
+                    let (cut1, cut2) = if mid - lo >= hi - mid {
+                        let cut1 = lo + (mid - lo) / 2;
+                        (cut1, v.partition_point(mid, hi, cut1, Ordering::is_lt))
+                    } else {
+                        let cut2 = mid + (hi - mid) / 2;
+                        (v.partition_point(lo, mid, cut2, Ordering::is_le), cut2)
+                    };
+                    //rotating cut1..mid and mid..cut2 leaves two smaller merges
+                    let new_mid = cut1 + (cut2 - mid);
+                    self.stack.push(Frame::Merge(new_mid, cut2, hi));
+                    self.stack.push(Frame::Merge(lo, cut1, new_mid));
+                    self.reversals.push((cut1, cut2));
+                    self.reversals.push((mid, cut2));
+                    self.reversals.push((cut1, mid));
+                    return false;
+                }
+            }
+        }
+    }
+
+    fn color(&self, i: usize) -> Option<Color> {
+        let (lo, hi) = self.range?;
+        match self.swap {
+            Some((a, _)) if i == a => Some(Color::RED),
+            Some((_, b)) if i == b => Some(Color::BLUE),
+            _ if i >= lo && i < hi => Some(Color::YELLOW),
+            _ => None,
+        }
+    }
+
+    fn cursor(&self) -> usize {
+        self.swap.map_or(0, |(a, _)| a)
+    }
+
+    fn status(&self) -> Option<String> {
+        Some(format!(
+            "Auxiliary memory: 0 elements, recursion depth {}",
+            depth(&self.stack)
+        ))
+    }
+}
+
+#[test]
+fn merge_sort_test() {
+    //checking if merge sort is correct for one random vec for each len 1-1000
+    use super::{is_sorted, run_to_end};
+    use rand::{thread_rng, Rng};
+
+    for len in 1..=1000 {
+        let mut v: Vec<i32> = (0..len).map(|_| thread_rng().gen::<i32>()).collect();
+        run_to_end(&mut MergeSort::default(), &mut v);
+        assert!(is_sorted(&v));
+    }
+}
+
+#[test]
+fn top_down_merge_sort_test() {
+    //checking if top-down merge sort is correct for one random vec for each len 1-1000
+    use super::{is_sorted, run_to_end};
+    use rand::{thread_rng, Rng};
+
+    for len in 1..=1000 {
+        let mut v: Vec<i32> = (0..len).map(|_| thread_rng().gen::<i32>()).collect();
+        run_to_end(&mut TopDownMergeSort::default(), &mut v);
+        assert!(is_sorted(&v));
+    }
+}
+
+#[test]
+fn in_place_merge_sort_test() {
+    //checking if in-place merge sort is correct for one random vec with duplicates for each len 1-1000
+    use super::{is_sorted, run_to_end};
+    use rand::{thread_rng, Rng};
+
+    for len in 1..=1000 {
+        let mut v: Vec<i32> = (0..len)
+            .map(|_| thread_rng().gen_range(0..len / 2 + 1))
+            .collect();
+        run_to_end(&mut InPlaceMergeSort::default(), &mut v);
+        assert!(is_sorted(&v));
+    }
+}
@@ -0,0 +1,223 @@
+use bevy::prelude::Color;
+use serde::{Deserialize, Serialize};
+
+mod adversary;
+mod cost;
+mod distribution;
+mod exchange;
+mod export;
+mod heap;
+mod input;
+mod insertion;
+mod intro;
+mod merge;
+mod quick;
+mod race;
+mod radix;
+mod records;
+mod schrodinger;
+mod selection;
+mod settings;
+mod shell;
+mod std_sort;
+mod timsort;
+mod trace;
+mod verdict;
+
+pub use adversary::{has_worst_case, worst_case};
+pub use cost::{CostChart, Metric};
+pub use distribution::{BucketSort, CountingSort, PigeonholeSort, MAX_SLOTS};
+pub use exchange::{BubbleSort, CocktailShakerSort, CombSort, GnomeSort, OddEvenSort};
+pub use export::SavedRun;
+pub use heap::HeapSort;
+pub use input::{parse_list, Distribution, MAX_ITEMS};
+pub use insertion::InsertionSort;
+pub use intro::{IntroSort, PdqSort};
+pub use merge::{InPlaceMergeSort, MergeSort, TopDownMergeSort};
+pub use quick::{Partition, PivotRule, QuickSort};
+pub use race::Race;
+pub use radix::{DigitOrder, RadixSort, BASES};
+pub use records::{
+    pack_records, record, record_color, record_key, record_position, repack_records,
+    unpack_records, MAX_KEY,
+};
+pub use schrodinger::SchrödingerSort;
+pub use selection::SelectionSort;
+pub use settings::SortSettings;
+pub use shell::{GapSequence, ShellSort};
+pub use std_sort::{StdSort, StdSortKind};
+pub use timsort::{NaturalMergeSort, TimSort};
+pub use trace::{Counters, Event, Replay, Rewind, Trace, Traced};
+pub use verdict::Verdict;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Algorithm {
+    Insertion,
+    Selection,
+    Merge,
+    TopDownMerge,
+    InPlaceMerge,
+    NaturalMerge,
+    Tim,
+    Quick,
+    Intro,
+    Pdq,
+    Std,
+    Heap,
+    Shell,
+    Radix,
+    Counting,
+    Bucket,
+    Pigeonhole,
+    Bubble,
+    Cocktail,
+    OddEven,
+    Comb,
+    Gnome,
+    Schrödinger,
+    Validation,
+}
+
+impl Algorithm {
+    //whether the algorithm can sort the list, counting and pigeonhole sort need a slot for every value in its range
+    pub fn check(self, v: &[i32], records: bool) -> Result<(), String> {
+        let slots = distribution::slots(v, records);
+        if matches!(self, Algorithm::Counting | Algorithm::Pigeonhole) && slots > MAX_SLOTS {
+            Err(format!(
+                "{:?} sort needs {} slots for the range of values, at most {} are supported",
+                self, slots, MAX_SLOTS
+            ))
+        } else {
+            Ok(())
+        }
+    }
+
+    //every algorithm that can be picked in the controller, in the order they are listed
+    pub const ALL: [Algorithm; 23] = [
+        Algorithm::Insertion,
+        Algorithm::Selection,
+        Algorithm::Merge,
+        Algorithm::TopDownMerge,
+        Algorithm::InPlaceMerge,
+        Algorithm::NaturalMerge,
+        Algorithm::Tim,
+        Algorithm::Quick,
+        Algorithm::Intro,
+        Algorithm::Pdq,
+        Algorithm::Std,
+        Algorithm::Heap,
+        Algorithm::Shell,
+        Algorithm::Radix,
+        Algorithm::Counting,
+        Algorithm::Bucket,
+        Algorithm::Pigeonhole,
+        Algorithm::Bubble,
+        Algorithm::Cocktail,
+        Algorithm::OddEven,
+        Algorithm::Comb,
+        Algorithm::Gnome,
+        Algorithm::Schrödinger,
+    ];
+
+    //creates a fresh stepper positioned at the start of the algorithm
+    //Validation is not a sorting algorithm and has no stepper
+    pub fn stepper(self, settings: &SortSettings) -> Box<dyn SortStepper> {
+        match self {
+            Algorithm::Insertion => Box::<InsertionSort>::default(),
+            Algorithm::Selection => Box::<SelectionSort>::default(),
+            Algorithm::Merge => Box::<MergeSort>::default(),
+            Algorithm::TopDownMerge => Box::<TopDownMergeSort>::default(),
+            Algorithm::InPlaceMerge => Box::<InPlaceMergeSort>::default(),
+            Algorithm::NaturalMerge => Box::<NaturalMergeSort>::default(),
+            Algorithm::Tim => Box::<TimSort>::default(),
+            Algorithm::Quick => Box::new(QuickSort::new(
+                settings.partition,
+                settings.pivot,
+                settings.seed,
+            )),
+            Algorithm::Intro => Box::<IntroSort>::default(),
+            Algorithm::Pdq => Box::<PdqSort>::default(),
+            Algorithm::Std => Box::new(StdSort::new(settings.std_sort)),
+            Algorithm::Heap => Box::<HeapSort>::default(),
+            Algorithm::Shell => Box::new(ShellSort::new(settings.gaps)),
+            Algorithm::Radix => Box::new(RadixSort::new(settings.digits, settings.base)),
+            Algorithm::Counting => Box::<CountingSort>::default(),
+            Algorithm::Bucket => Box::new(BucketSort::new(settings.buckets)),
+            Algorithm::Pigeonhole => Box::<PigeonholeSort>::default(),
+            Algorithm::Bubble => Box::new(BubbleSort::new(settings.early_exit)),
+            Algorithm::Cocktail => Box::<CocktailShakerSort>::default(),
+            Algorithm::OddEven => Box::<OddEvenSort>::default(),
+            Algorithm::Comb => Box::new(CombSort::new(settings.shrink)),
+            Algorithm::Gnome => Box::<GnomeSort>::default(),
+            Algorithm::Schrödinger => Box::new(SchrödingerSort::new(settings.seed)),
+            Algorithm::Validation => unreachable!("validation has no stepper"),
+        }
+    }
+}
+
+//copies a boxed stepper, so a rewind can keep snapshots of the run
+pub trait CloneStepper {
+    fn clone_stepper(&self) -> Box<dyn SortStepper>;
+}
+
+impl<T: SortStepper + Clone + 'static> CloneStepper for T {
+    fn clone_stepper(&self) -> Box<dyn SortStepper> {
+        Box::new(self.clone())
+    }
+}
+
+//a sorting algorithm that can be advanced one operation at a time
+pub trait SortStepper: CloneStepper + Send + Sync {
+    //performs one operation on the list, returns true once the list is sorted
+    fn step(&mut self, v: &mut Traced) -> bool;
+
+    //color of the bar at index i while the algorithm is running, None keeps it white
+    fn color(&self, i: usize) -> Option<Color>;
+
+    //index of the pointer that the sound pitch follows
+    fn cursor(&self) -> usize;
+
+    //size of the binary heap kept at the start of the list, for algorithms that keep one
+    fn heap_size(&self) -> Option<usize> {
+        None
+    }
+
+    //auxiliary buckets the elements are currently distributed into
+    fn buckets(&self) -> Vec<Vec<i32>> {
+        vec![]
+    }
+
+    //count of every value, drawn as a histogram under the list
+    fn histogram(&self) -> Vec<usize> {
+        vec![]
+    }
+
+    //extra information about the state of the algorithm shown in the controller
+    fn status(&self) -> Option<String> {
+        None
+    }
+
+    //algorithm a hybrid sort is currently falling back to, shown next to its name
+    fn mode(&self) -> Option<&str> {
+        None
+    }
+}
+
+//distinct color for the n-th run or segment of an algorithm
+pub fn palette(n: usize) -> Color {
+    Color::hsl((n as f32 * 137.5) % 360., 0.8, 0.6)
+}
+
+//runs a stepper until it reports that the list is sorted
+#[cfg(test)]
+pub fn run_to_end(stepper: &mut dyn SortStepper, v: &mut [i32]) {
+    let mut events = vec![];
+    while !stepper.step(&mut Traced::new(v, &mut events)) {
+        events.clear();
+    }
+}
+
+#[cfg(test)]
+pub fn is_sorted(v: &[i32]) -> bool {
+    v.windows(2).all(|w| w[0] <= w[1])
+}
@@ -0,0 +1,345 @@
+use super::{SortStepper, Traced};
+use bevy::prelude::Color;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Partition {
+    Lomuto,
+    Hoare,
+}
+
+impl Partition {
+    pub const ALL: [Partition; 2] = [Partition::Lomuto, Partition::Hoare];
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum PivotRule {
+    First,
+    Last,
+    Random,
+    MedianOfThree,
+}
+
+impl PivotRule {
+    pub const ALL: [PivotRule; 4] = [
+        PivotRule::First,
+        PivotRule::Last,
+        PivotRule::Random,
+        PivotRule::MedianOfThree,
+    ];
+
+    //index of the pivot for the sub-range lo..=hi
+    pub fn pick(self, v: &mut Traced, lo: usize, hi: usize, rng: &mut impl Rng) -> usize {
+        match self {
+            PivotRule::First => lo,
+            PivotRule::Last => hi,
+            PivotRule::Random => rng.gen_range(lo..=hi),
+            PivotRule::MedianOfThree => {
+                let mid = lo + (hi - lo) / 2;
+                if v.compare(lo, mid).is_le() == v.compare(mid, hi).is_le() {
+                    mid
+                } else if v.compare(mid, lo).is_le() == v.compare(lo, hi).is_le() {
+                    lo
+                } else {
+                    hi
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Phase {
+    Pick,  //the pivot has not been chosen yet
+    Left,  //moving the left ptr towards the right
+    Right, //moving the right ptr towards the left
+}
+
+//the parts of a partitioned range, left and right are half open
+pub(super) struct Parts {
+    pub(super) left: (usize, usize),
+    pub(super) pivot: Option<usize>, //final position of the pivot, hoare does not place it
+    pub(super) right: (usize, usize),
+}
+
+//partitioning of lo..hi around a pivot, one comparison or swap per operation
+#[derive(Clone)]
+pub(super) struct Partitioning {
+    partition: Partition,
+    rule: PivotRule,
+    pub(super) equal_left: bool, //lomuto moves elements equal to the pivot to its left
+    pub(super) lo: usize,
+    pub(super) hi: usize,
+    phase: Phase,
+    pub(super) pivot_index: usize,
+    i: usize,                //left ptr, the store ptr in lomuto
+    j: usize,                //right ptr, the scanning ptr in lomuto
+    pub(super) swaps: usize, //swaps done by the partitioning itself
+    seed: u64,               //seed of the random pivot
+}
+
+impl Partitioning {
+    pub(super) fn new(
+        partition: Partition,
+        rule: PivotRule,
+        lo: usize,
+        hi: usize,
+        seed: u64,
+    ) -> Self {
+        Partitioning {
+            partition,
+            rule,
+            equal_left: false,
+            lo,
+            hi,
+            phase: Phase::Pick,
+            pivot_index: lo,
+            i: lo,
+            j: lo,
+            swaps: 0,
+            seed,
+        }
+    }
+
+    pub(super) fn picked(&self) -> bool {
+        self.phase != Phase::Pick
+    }
+
+    fn swap(&mut self, v: &mut Traced, a: usize, b: usize) {
+        v.swap(a, b);
+        self.swaps += 1;
+        if self.pivot_index == a {
+            self.pivot_index = b;
+        } else if self.pivot_index == b {
+            self.pivot_index = a;
+        }
+    }
+
+    //one operation, returns the parts once the range is partitioned
+    pub(super) fn step(&mut self, v: &mut Traced) -> Option<Parts> {
+        let (lo, hi) = (self.lo, self.hi);
+        if self.phase == Phase::Pick {
+            let mut rng = StdRng::seed_from_u64(self.seed);
+            let pivot = self.rule.pick(v, lo, hi - 1, &mut rng);
+            //lomuto keeps the pivot at the end of the range, hoare at the start
+            let target = match self.partition {
+                Partition::Lomuto => hi - 1,
+                Partition::Hoare => lo,
+            };
+            if pivot != target {
+                v.swap(pivot, target);
+            }
+            self.pivot_index = target;
+            (self.i, self.j) = match self.partition {
+                Partition::Lomuto => (lo, lo),
+                Partition::Hoare => (lo, hi - 1),
+            };
+            self.phase = Phase::Left;
+            None
+        } else if self.partition == Partition::Lomuto {
+            self.lomuto(v)
+        } else {
+            self.hoare(v)
+        }
+    }
+
+    fn lomuto(&mut self, v: &mut Traced) -> Option<Parts> {
+        let last = self.hi - 1;
+        if self.j < last {
+            let order = v.compare(self.j, last);
+            if order.is_lt() || (self.equal_left && order.is_eq()) {
+                if self.i != self.j {
+                    self.swap(v, self.i, self.j);
+                }
+                self.i += 1;
+            }
+            self.j += 1;
+            None
+        } else {
+            let i = self.i;
+            if i != last {
+                v.swap(i, last);
+            }
+            self.pivot_index = i;
+            Some(Parts {
+                left: (self.lo, i),
+                pivot: Some(i),
+                right: (i + 1, self.hi),
+            })
+        }
+    }
+
+    fn hoare(&mut self, v: &mut Traced) -> Option<Parts> {
+        if self.phase == Phase::Left {
+            if v.compare(self.i, self.pivot_index).is_lt() {
+                self.i += 1;
+            } else {
+                self.phase = Phase::Right;
+            }
+        } else if v.compare(self.j, self.pivot_index).is_gt() {
+            self.j -= 1;
+        } else if self.i >= self.j {
+            return Some(Parts {
+                left: (self.lo, self.j + 1),
+                pivot: None,
+                right: (self.j + 1, self.hi),
+            });
+        } else {
+            self.swap(v, self.i, self.j);
+            self.i += 1;
+            self.j -= 1;
+            self.phase = Phase::Left;
+        }
+        None
+    }
+
+    pub(super) fn color(&self, i: usize) -> Option<Color> {
+        if self.phase != Phase::Pick {
+            if i == self.pivot_index {
+                return Some(Color::BLUE);
+            } else if i == self.i {
+                return Some(Color::RED);
+            } else if i == self.j {
+                return Some(Color::ORANGE);
+            }
+        }
+        if i >= self.lo && i < self.hi {
+            Some(Color::YELLOW)
+        } else {
+            None
+        }
+    }
+
+    pub(super) fn cursor(&self) -> usize {
+        self.i
+    }
+}
+
+//quicksort with an explicit stack of sub-ranges so it can be paused between operations
+#[derive(Clone)]
+pub struct QuickSort {
+    partition: Partition,
+    pivot: PivotRule,
+    stack: Vec<(usize, usize)>, //sub-ranges left to partition
+    partitioning: Option<Partitioning>,
+    placed: Vec<bool>, //elements known to be in their final position
+    rng: StdRng,       //seeds the random pivots of every partitioning
+    started: bool,
+}
+
+impl QuickSort {
+    pub fn new(partition: Partition, pivot: PivotRule, seed: u64) -> Self {
+        QuickSort {
+            partition,
+            pivot,
+            stack: vec![],
+            partitioning: None,
+            placed: vec![],
+            rng: StdRng::seed_from_u64(seed),
+            started: false,
+        }
+    }
+
+    fn push(&mut self, (lo, hi): (usize, usize)) {
+        if hi > lo + 1 {
+            self.stack.push((lo, hi));
+        } else if hi == lo + 1 {
+            self.placed[lo] = true;
+        }
+    }
+}
+
+impl SortStepper for QuickSort {
+    fn step(&mut self, v: &mut Traced) -> bool {
+        if !self.started {
+            self.started = true;
+            self.placed = vec![false; v.len()];
+            self.push((0, v.len()));
+        }
+        let partitioning = match &mut self.partitioning {
+            Some(partitioning) => partitioning,
+            None => match self.stack.pop() {
+                Some((lo, hi)) => {
+                    let seed = self.rng.gen();
+                    self.partitioning.insert(Partitioning::new(
+                        self.partition,
+                        self.pivot,
+                        lo,
+                        hi,
+                        seed,
+                    ))
+                }
+                None => return true,
+            },
+        };
+        if let Some(parts) = partitioning.step(v) {
+            self.partitioning = None;
+            if let Some(pivot) = parts.pivot {
+                self.placed[pivot] = true;
+            }
+            self.push(parts.right);
+            self.push(parts.left);
+        }
+        false
+    }
+
+    fn color(&self, i: usize) -> Option<Color> {
+        if let Some(color) = self.partitioning.as_ref().and_then(|p| p.color(i)) {
+            Some(color)
+        } else if self.placed.get(i) == Some(&true) {
+            Some(Color::GREEN)
+        } else {
+            None
+        }
+    }
+
+    fn cursor(&self) -> usize {
+        self.partitioning.as_ref().map_or(0, Partitioning::cursor)
+    }
+}
+
+#[test]
+fn quick_sort_test() {
+    //checking if quicksort is correct with every partition scheme and pivot rule for one random vec for each len 1-1000
+    use super::{is_sorted, run_to_end};
+    use rand::thread_rng;
+
+    for partition in Partition::ALL {
+        for pivot in PivotRule::ALL {
+            for len in 1..=1000 {
+                let mut v: Vec<i32> = (0..len).map(|_| thread_rng().gen_range(0..len)).collect();
+                run_to_end(&mut QuickSort::new(partition, pivot, len as u64), &mut v);
+                assert!(is_sorted(&v));
+            }
+        }
+    }
+}
+
+#[test]
+fn seed_test() {
+    //checking that random pivots and schrödinger shuffles repeat with the same seed
+    use super::{SchrödingerSort, Trace};
+    use rand::thread_rng;
+
+    let v: Vec<i32> = (0..200).map(|_| thread_rng().gen_range(0..100)).collect();
+    let record = |stepper: &mut dyn SortStepper, steps: usize| {
+        let mut trace = Trace::new(v.clone());
+        let mut list = v.clone();
+        for _ in 0..steps {
+            trace.step(stepper, &mut list);
+        }
+        (0..trace.operations())
+            .map(|i| trace.operation(i).to_vec())
+            .collect::<Vec<_>>()
+    };
+    for partition in Partition::ALL {
+        let first = record(&mut QuickSort::new(partition, PivotRule::Random, 7), 5000);
+        let second = record(&mut QuickSort::new(partition, PivotRule::Random, 7), 5000);
+        assert_eq!(first, second);
+    }
+    let first = record(&mut SchrödingerSort::new(7), 20);
+    assert_eq!(first, record(&mut SchrödingerSort::new(7), 20));
+    assert_ne!(first, record(&mut SchrödingerSort::new(8), 20));
+}
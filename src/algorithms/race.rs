@@ -0,0 +1,111 @@
+use super::{Algorithm, Counters, SortSettings, SortStepper, Traced};
+use bevy::prelude::Color;
+
+//one algorithm of a race, sorting its own copy of the list
+pub struct Lane {
+    pub algorithm: Algorithm,
+    pub v: Vec<i32>,
+    pub counters: Counters,
+    pub operations: usize, //operations done so far, or until the lane finished
+    pub done: bool,
+    stepper: Box<dyn SortStepper>,
+}
+
+impl Lane {
+    pub fn color(&self, i: usize) -> Option<Color> {
+        if self.done {
+            Some(Color::GREEN)
+        } else {
+            self.stepper.color(i)
+        }
+    }
+}
+
+//algorithms sorting identical copies of a list, advanced by the same number of operations
+pub struct Race {
+    pub lanes: Vec<Lane>,
+    pub finish: Vec<usize>, //lanes in the order they finished
+    pub records: bool,      //the lanes sort records by their keys
+}
+
+impl Race {
+    pub fn new(algorithms: &[Algorithm], settings: &SortSettings, v: &[i32]) -> Self {
+        let lanes = algorithms
+            .iter()
+            .map(|&algorithm| Lane {
+                algorithm,
+                v: v.to_vec(),
+                counters: Counters::default(),
+                operations: 0,
+                done: false,
+                stepper: algorithm.stepper(settings),
+            })
+            .collect();
+        Race {
+            lanes,
+            finish: vec![],
+            records: settings.records,
+        }
+    }
+
+    //advances every lane that is still sorting by the given number of operations
+    //lanes finishing within the same call are placed by the operations they needed
+    pub fn step(&mut self, operations: u32) {
+        let mut finished = vec![];
+        let mut events = vec![];
+        for (i, lane) in self.lanes.iter_mut().enumerate() {
+            for _ in 0..operations {
+                if lane.done {
+                    break;
+                }
+                let mut traced = Traced::new(&mut lane.v, &mut events).with_records(self.records);
+                lane.done = lane.stepper.step(&mut traced);
+                for event in events.drain(..) {
+                    lane.counters.add(event);
+                }
+                if lane.done {
+                    finished.push(i);
+                } else {
+                    lane.operations += 1;
+                }
+            }
+        }
+        finished.sort_by_key(|&i| self.lanes[i].operations);
+        self.finish.extend(finished);
+    }
+
+    pub fn finished(&self) -> bool {
+        self.finish.len() == self.lanes.len()
+    }
+
+    //place of the lane in the finish order, counting from 1
+    pub fn place(&self, lane: usize) -> Option<usize> {
+        self.finish.iter().position(|&i| i == lane).map(|p| p + 1)
+    }
+}
+
+#[test]
+fn race_test() {
+    //checking that insertion sort beats merge sort on a nearly sorted list and that every lane ends sorted
+    use super::is_sorted;
+
+    let mut v: Vec<i32> = (1..=200).collect();
+    v.swap(50, 51);
+    v.swap(120, 121);
+    let algorithms = [Algorithm::Merge, Algorithm::Insertion, Algorithm::Heap];
+    let mut race = Race::new(&algorithms, &SortSettings::default(), &v);
+    while !race.finished() {
+        race.step(7);
+    }
+    assert_eq!(race.place(1), Some(1));
+    for lane in &race.lanes {
+        assert!(is_sorted(&lane.v));
+        assert!(lane.counters.comparisons > 0 || lane.counters.writes > 0);
+    }
+    let operations: Vec<usize> = race
+        .finish
+        .iter()
+        .map(|&i| race.lanes[i].operations)
+        .collect();
+    assert!(operations.windows(2).all(|w| w[0] <= w[1]));
+}
@@ -0,0 +1,211 @@
+use super::{SortStepper, Traced};
+use bevy::prelude::Color;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum DigitOrder {
+    Lsd,
+    Msd,
+}
+
+impl DigitOrder {
+    pub const ALL: [DigitOrder; 2] = [DigitOrder::Lsd, DigitOrder::Msd];
+}
+
+pub const BASES: [u64; 5] = [2, 4, 10, 16, 256];
+
+//radix sort distributing the list into buckets by one digit per pass and collecting them back
+//every read into a bucket and every write back into the list is one operation
+#[derive(Clone)]
+pub struct RadixSort {
+    order: DigitOrder,
+    base: u64,
+    min: i64, //keys are offset by the minimum so negative values sort too
+    stack: Vec<(usize, usize, u32)>, //ranges left to sort and the digit to sort them by
+    range: Option<(usize, usize, u32)>, //range being distributed or collected
+    buckets: Vec<Vec<i32>>,
+    read: usize,   //next element to distribute
+    write: usize,  //next position to collect into
+    bucket: usize, //bucket being collected
+    taken: usize,  //elements already collected from that bucket
+    collecting: bool,
+    started: bool,
+}
+
+impl RadixSort {
+    pub fn new(order: DigitOrder, base: u64) -> Self {
+        RadixSort {
+            order,
+            base,
+            min: 0,
+            stack: vec![],
+            range: None,
+            buckets: vec![],
+            read: 0,
+            write: 0,
+            bucket: 0,
+            taken: 0,
+            collecting: false,
+            started: false,
+        }
+    }
+
+    fn digit(&self, x: i32, digit: u32) -> usize {
+        ((x as i64 - self.min) as u64 / self.base.pow(digit) % self.base) as usize
+    }
+
+    fn start(&mut self, v: &mut Traced) {
+        self.started = true;
+        let values = v.read_all();
+        let keys: Vec<i32> = values.iter().map(|&x| v.key(x)).collect();
+        let (Some(&min), Some(&max)) = (keys.iter().min(), keys.iter().max()) else {
+            return;
+        };
+        self.min = min as i64;
+        let key = (max as i64 - self.min) as u64;
+        let mut digits = 1;
+        while self.base.checked_pow(digits).is_some_and(|p| p <= key) {
+            digits += 1;
+        }
+        match self.order {
+            DigitOrder::Lsd => {
+                for digit in (0..digits).rev() {
+                    self.stack.push((0, v.len(), digit));
+                }
+            }
+            DigitOrder::Msd => self.stack.push((0, v.len(), digits - 1)),
+        }
+    }
+
+    //after collecting a range msd continues with every bucket on the next digit
+    fn finish_range(&mut self, lo: usize, digit: u32) {
+        self.range = None;
+        self.collecting = false;
+        if self.order == DigitOrder::Msd && digit > 0 {
+            let mut start = lo;
+            for bucket in &self.buckets {
+                if bucket.len() > 1 {
+                    self.stack.push((start, start + bucket.len(), digit - 1));
+                }
+                start += bucket.len();
+            }
+        }
+        self.buckets.clear();
+    }
+}
+
+impl SortStepper for RadixSort {
+    fn step(&mut self, v: &mut Traced) -> bool {
+        if !self.started {
+            self.start(v);
+        }
+        loop {
+            let (lo, hi, digit) = match self.range {
+                Some(range) => range,
+                None => match self.stack.pop() {
+                    Some(range) => {
+                        self.range = Some(range);
+                        self.buckets = vec![vec![]; self.base as usize];
+                        (self.read, self.write) = (range.0, range.0);
+                        (self.bucket, self.taken) = (0, 0);
+                        range
+                    }
+                    None => return true,
+                },
+            };
+            if !self.collecting {
+                if self.read < hi {
+                    let x = v.take(self.read);
+                    let bucket = self.digit(v.key(x), digit);
+                    self.buckets[bucket].push(x);
+                    self.read += 1;
+                    return false;
+                }
+                self.collecting = true;
+            }
+            while self.bucket < self.buckets.len() && self.taken >= self.buckets[self.bucket].len()
+            {
+                self.bucket += 1;
+                self.taken = 0;
+            }
+            if self.bucket < self.buckets.len() {
+                v.set(self.write, self.buckets[self.bucket][self.taken]);
+                self.taken += 1;
+                self.write += 1;
+                return false;
+            }
+            self.finish_range(lo, digit);
+        }
+    }
+
+    fn color(&self, i: usize) -> Option<Color> {
+        let (lo, hi, _) = self.range?;
+        if self.collecting {
+            if i == self.write {
+                return Some(Color::RED);
+            } else if i >= lo && i < self.write {
+                return Some(Color::BLUE);
+            }
+        } else if i == self.read {
+            return Some(Color::RED);
+        } else if i >= lo && i < self.read {
+            return Some(Color::GRAY);
+        }
+        if self.order == DigitOrder::Msd && i >= lo && i < hi {
+            Some(Color::YELLOW)
+        } else {
+            None
+        }
+    }
+
+    fn cursor(&self) -> usize {
+        if self.collecting {
+            self.write
+        } else {
+            self.read
+        }
+    }
+
+    fn buckets(&self) -> Vec<Vec<i32>> {
+        if !self.collecting {
+            return self.buckets.clone();
+        }
+        //buckets shrink as they are collected back into the list
+        let mut buckets = self.buckets.clone();
+        for (b, bucket) in buckets.iter_mut().enumerate() {
+            if b < self.bucket {
+                bucket.clear();
+            } else if b == self.bucket {
+                bucket.drain(..self.taken);
+            }
+        }
+        buckets
+    }
+
+    fn status(&self) -> Option<String> {
+        let (_, _, digit) = self.range?;
+        Some(format!(
+            "{:?}, base {}, digit {}",
+            self.order,
+            self.base,
+            digit + 1
+        ))
+    }
+}
+
+#[test]
+fn radix_sort_test() {
+    //checking if radix sort is correct in both orders and every base for one random vec for each len 1-1000
+    use super::{is_sorted, run_to_end};
+    use rand::{thread_rng, Rng};
+
+    for order in DigitOrder::ALL {
+        for base in BASES {
+            for len in 1..=1000 {
+                let mut v: Vec<i32> = (0..len).map(|_| thread_rng().gen::<i32>()).collect();
+                run_to_end(&mut RadixSort::new(order, base), &mut v);
+                assert!(is_sorted(&v));
+            }
+        }
+    }
+}
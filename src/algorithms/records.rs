@@ -0,0 +1,128 @@
+use super::MAX_ITEMS;
+use bevy::prelude::Color;
+
+//a record packs a key with the position its element started at as key * WIDTH + position,
+//so elements with equal keys stay tellable apart and the order they end up in shows stability
+const WIDTH: i32 = 2048;
+
+//keys that still fit into a record
+pub const MAX_KEY: i32 = i32::MAX / WIDTH - 1;
+
+const _: () = assert!(MAX_ITEMS < WIDTH as usize);
+
+//records of the keys at their current positions, None if a key does not fit
+pub fn pack_records(keys: &[i32]) -> Option<Vec<i32>> {
+    keys.iter()
+        .zip(0..)
+        .map(|(&key, position)| (key.abs() <= MAX_KEY).then_some(record(key, position)))
+        .collect()
+}
+
+//record of a key that fits, started at the position
+pub fn record(key: i32, position: usize) -> i32 {
+    key.clamp(-MAX_KEY, MAX_KEY) * WIDTH + position as i32
+}
+
+//records packed again with the positions they are at now, so a new run starts from this order
+pub fn repack_records(v: &[i32]) -> Vec<i32> {
+    v.iter()
+        .zip(0..)
+        .map(|(&x, position)| record(record_key(x), position))
+        .collect()
+}
+
+pub fn unpack_records(v: &[i32]) -> Vec<i32> {
+    v.iter().map(|&x| record_key(x)).collect()
+}
+
+pub fn record_key(x: i32) -> i32 {
+    x.div_euclid(WIDTH)
+}
+
+pub fn record_position(x: i32) -> usize {
+    x.rem_euclid(WIDTH) as usize
+}
+
+//records are colored by where they started, going from red to violet over a list of len records
+pub fn record_color(x: i32, len: usize) -> Color {
+    let hue = 270. * record_position(x) as f32 / len.max(1) as f32;
+    Color::hsl(hue, 0.8, 0.6)
+}
+
+//indices i where records i - 1 and i have equal keys but have swapped their original order
+pub fn unstable_pairs(v: &[i32]) -> Vec<usize> {
+    (1..v.len())
+        .filter(|&i| {
+            record_key(v[i - 1]) == record_key(v[i])
+                && record_position(v[i - 1]) > record_position(v[i])
+        })
+        .collect()
+}
+
+#[test]
+fn records_test() {
+    //checking that records keep their keys and positions, negative keys included, and which
+    //sorts keep equal keys in order
+    use super::Algorithm::*;
+    use super::{is_sorted, run_to_end, InsertionSort, SelectionSort, SortSettings};
+    use super::{SortStepper, Traced};
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    let keys = vec![3, -2, 3, 0, -2, MAX_KEY, -MAX_KEY];
+    let records = pack_records(&keys).unwrap();
+    assert_eq!(unpack_records(&records), keys);
+    let positions: Vec<usize> = records.iter().map(|&x| record_position(x)).collect();
+    assert_eq!(positions, (0..keys.len()).collect::<Vec<_>>());
+    assert!(pack_records(&[MAX_KEY + 1]).is_none());
+
+    let sort = |stepper: &mut dyn SortStepper, mut v: Vec<i32>| {
+        let mut events = vec![];
+        while !stepper.step(&mut Traced::new(&mut v, &mut events).with_records(true)) {}
+        v
+    };
+    let records = pack_records(&[2, 1, 2, 1, 2, 1]).unwrap();
+    let insertion = sort(&mut InsertionSort::default(), records.clone());
+    assert_eq!(unpack_records(&insertion), vec![1, 1, 1, 2, 2, 2]);
+    assert!(unstable_pairs(&insertion).is_empty());
+    let selection = sort(&mut SelectionSort::default(), records);
+    assert_eq!(unpack_records(&selection), vec![1, 1, 1, 2, 2, 2]);
+    assert!(!unstable_pairs(&selection).is_empty());
+
+    //a second run is judged against the order the first one left, not the one before it
+    let chained = sort(&mut InsertionSort::default(), selection.clone());
+    assert!(!unstable_pairs(&chained).is_empty());
+    let chained = sort(&mut InsertionSort::default(), repack_records(&selection));
+    assert!(unstable_pairs(&chained).is_empty());
+    assert_eq!(unpack_records(&chained), unpack_records(&selection));
+
+    //the merge sorts, counting sort and the stable std sort keep equal keys in order, heap sort and quicksort do not
+    let mut rng = StdRng::seed_from_u64(7);
+    let keys: Vec<i32> = (0..200).map(|_| rng.gen_range(1..=5)).collect();
+    let records = pack_records(&keys).unwrap();
+    let settings = SortSettings::default();
+    let stable = [
+        Merge,
+        TopDownMerge,
+        InPlaceMerge,
+        NaturalMerge,
+        Tim,
+        Std,
+        Counting,
+    ];
+    for algorithm in stable {
+        let sorted = sort(algorithm.stepper(&settings).as_mut(), records.clone());
+        assert!(is_sorted(&unpack_records(&sorted)), "{:?}", algorithm);
+        assert!(unstable_pairs(&sorted).is_empty(), "{:?}", algorithm);
+    }
+    for algorithm in [Heap, Quick] {
+        let sorted = sort(algorithm.stepper(&settings).as_mut(), records.clone());
+        assert!(is_sorted(&unpack_records(&sorted)), "{:?}", algorithm);
+        assert!(!unstable_pairs(&sorted).is_empty(), "{:?}", algorithm);
+    }
+
+    //plain values are unaffected by the records
+    let mut v = vec![5, 3, 4];
+    run_to_end(&mut InsertionSort::default(), &mut v);
+    assert_eq!(v, vec![3, 4, 5]);
+}
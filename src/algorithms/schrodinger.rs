@@ -0,0 +1,44 @@
+use super::{SortStepper, Traced};
+use bevy::prelude::Color;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+//shuffles the list until it happens to be sorted
+//the list is only considered sorted once it is observed, see the Observed resource
+#[derive(Clone)]
+pub struct SchrödingerSort {
+    first: usize, //value of the first element after the last shuffle
+    rng: StdRng,
+}
+
+impl SchrödingerSort {
+    pub fn new(seed: u64) -> Self {
+        SchrödingerSort {
+            first: 0,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+}
+
+impl SortStepper for SchrödingerSort {
+    fn step(&mut self, v: &mut Traced) -> bool {
+        if (1..v.len()).all(|i| v.compare(i - 1, i).is_le()) {
+            return true;
+        }
+        //fisher-yates shuffle
+        for i in (1..v.len()).rev() {
+            let j = self.rng.gen_range(0..=i);
+            v.swap(i, j);
+        }
+        self.first = v.get(0).max(0) as usize;
+        false
+    }
+
+    fn color(&self, _: usize) -> Option<Color> {
+        None
+    }
+
+    fn cursor(&self) -> usize {
+        self.first
+    }
+}
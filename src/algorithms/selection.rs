@@ -0,0 +1,62 @@
+use super::{SortStepper, Traced};
+use bevy::prelude::Color;
+
+#[derive(Default, Clone)]
+pub struct SelectionSort {
+    main: usize,      //index of main ptr
+    selection: usize, //index of selection ptr
+    selected: usize,  //index of selected value
+}
+
+impl SortStepper for SelectionSort {
+    fn step(&mut self, v: &mut Traced) -> bool {
+        if self.main >= v.len() {
+            return true;
+        }
+        if self.selection == self.main + 1 {
+            self.selected = self.main;
+        }
+        if self.selection < v.len() {
+            if v.compare(self.selection, self.selected).is_lt() {
+                self.selected = self.selection;
+            }
+            self.selection += 1;
+        } else {
+            if self.main != self.selected {
+                v.swap(self.main, self.selected);
+            }
+            self.main += 1;
+            self.selection = self.main + 1;
+        }
+        false
+    }
+
+    fn color(&self, i: usize) -> Option<Color> {
+        if i < self.main {
+            Some(Color::GREEN)
+        } else if i == self.selection {
+            Some(Color::RED)
+        } else if i == self.selected {
+            Some(Color::BLUE)
+        } else {
+            None
+        }
+    }
+
+    fn cursor(&self) -> usize {
+        self.selection
+    }
+}
+
+#[test]
+fn selection_sort_test() {
+    //checking if selection sort is correct for one random vec for each len 1-1000
+    use super::{is_sorted, run_to_end};
+    use rand::{thread_rng, Rng};
+
+    for len in 1..=1000 {
+        let mut v: Vec<i32> = (0..len).map(|_| thread_rng().gen::<i32>()).collect();
+        run_to_end(&mut SelectionSort::default(), &mut v);
+        assert!(is_sorted(&v));
+    }
+}
@@ -0,0 +1,136 @@
+use super::{Algorithm, DigitOrder, GapSequence, Partition, PivotRule, StdSortKind, BASES};
+use bevy::prelude::Resource;
+use bevy_egui::egui;
+use serde::{Deserialize, Serialize};
+use std::ops::RangeInclusive;
+
+//options of the algorithms that can be tuned in the controller before starting
+#[derive(Resource, Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SortSettings {
+    pub partition: Partition,
+    pub pivot: PivotRule,
+    pub gaps: GapSequence,
+    pub digits: DigitOrder,
+    pub base: u64,
+    pub buckets: usize,
+    pub early_exit: bool,
+    pub shrink: f32,
+    pub std_sort: StdSortKind,
+    #[serde(default)]
+    pub seed: u64, //drives the list generation and the randomized algorithms
+    #[serde(default)]
+    pub records: bool, //the list holds keyed records to show stability
+}
+
+impl Default for SortSettings {
+    fn default() -> Self {
+        SortSettings {
+            partition: Partition::Lomuto,
+            pivot: PivotRule::Last,
+            gaps: GapSequence::Ciura,
+            digits: DigitOrder::Lsd,
+            base: 10,
+            buckets: 10,
+            early_exit: true,
+            shrink: 1.3,
+            std_sort: StdSortKind::Stable,
+            seed: 0,
+            records: false,
+        }
+    }
+}
+
+impl SortSettings {
+    //ranges the controller offers, other values can keep the sorts from ever finishing
+    pub const BUCKETS: RangeInclusive<usize> = 1..=100;
+    pub const SHRINK: RangeInclusive<f32> = 1.1..=3.0;
+
+    //settings loaded from a file have to be ones the controller could have picked
+    pub fn check(&self) -> Result<(), String> {
+        if !BASES.contains(&self.base) {
+            Err(format!(
+                "Radix base {} is not one of {:?}",
+                self.base, BASES
+            ))
+        } else if !Self::BUCKETS.contains(&self.buckets) {
+            Err(format!(
+                "{} buckets is outside of {:?}",
+                self.buckets,
+                Self::BUCKETS
+            ))
+        } else if !Self::SHRINK.contains(&self.shrink) {
+            Err(format!(
+                "Shrink factor {} is outside of {:?}",
+                self.shrink,
+                Self::SHRINK
+            ))
+        } else {
+            Ok(())
+        }
+    }
+
+    //shows the options of the given algorithm, if it has any
+    pub fn ui(&mut self, ui: &mut egui::Ui, algorithm: Algorithm) {
+        if algorithm == Algorithm::Shell {
+            egui::ComboBox::from_label("Gap sequence")
+                .selected_text(format!("{:?}", self.gaps))
+                .show_ui(ui, |ui| {
+                    for gaps in GapSequence::ALL {
+                        ui.selectable_value(&mut self.gaps, gaps, format!("{:?}", gaps));
+                    }
+                });
+        }
+        if algorithm == Algorithm::Radix {
+            ui.horizontal(|ui| {
+                for digits in DigitOrder::ALL {
+                    ui.radio_value(&mut self.digits, digits, format!("{:?}", digits));
+                }
+            });
+            egui::ComboBox::from_label("Base")
+                .selected_text(self.base.to_string())
+                .show_ui(ui, |ui| {
+                    for base in BASES {
+                        ui.selectable_value(&mut self.base, base, base.to_string());
+                    }
+                });
+        }
+        if algorithm == Algorithm::Bucket {
+            ui.add(egui::Slider::new(&mut self.buckets, Self::BUCKETS).text("Buckets"));
+        }
+        if algorithm == Algorithm::Bubble {
+            ui.checkbox(&mut self.early_exit, "Stop after a pass without swaps");
+        }
+        if algorithm == Algorithm::Comb {
+            ui.add(egui::Slider::new(&mut self.shrink, Self::SHRINK).text("Shrink factor"));
+        }
+        if algorithm == Algorithm::Quick {
+            egui::ComboBox::from_label("Partition scheme")
+                .selected_text(format!("{:?}", self.partition))
+                .show_ui(ui, |ui| {
+                    for partition in Partition::ALL {
+                        ui.selectable_value(
+                            &mut self.partition,
+                            partition,
+                            format!("{:?}", partition),
+                        );
+                    }
+                });
+            egui::ComboBox::from_label("Pivot")
+                .selected_text(format!("{:?}", self.pivot))
+                .show_ui(ui, |ui| {
+                    for pivot in PivotRule::ALL {
+                        ui.selectable_value(&mut self.pivot, pivot, format!("{:?}", pivot));
+                    }
+                });
+        }
+        if algorithm == Algorithm::Std {
+            egui::ComboBox::from_label("Std sort")
+                .selected_text(self.std_sort.name())
+                .show_ui(ui, |ui| {
+                    for kind in StdSortKind::ALL {
+                        ui.selectable_value(&mut self.std_sort, kind, kind.name());
+                    }
+                });
+        }
+    }
+}
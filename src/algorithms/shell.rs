@@ -0,0 +1,169 @@
+use super::{SortStepper, Traced};
+use bevy::prelude::Color;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum GapSequence {
+    Shell,
+    Knuth,
+    Sedgewick,
+    Ciura,
+    Tokuda,
+}
+
+impl GapSequence {
+    pub const ALL: [GapSequence; 5] = [
+        GapSequence::Shell,
+        GapSequence::Knuth,
+        GapSequence::Sedgewick,
+        GapSequence::Ciura,
+        GapSequence::Tokuda,
+    ];
+
+    //gaps smaller than len in the order they are used, always ending with 1
+    pub fn gaps(self, len: usize) -> Vec<usize> {
+        //halvings of len that still leave a gap of at least 1
+        let halvings = len.max(1).ilog2();
+        let mut gaps = vec![1];
+        for k in 1.. {
+            let gap = match self {
+                GapSequence::Shell => len >> halvings.saturating_sub(k as u32),
+                GapSequence::Knuth => 3 * gaps[k - 1] + 1,
+                GapSequence::Sedgewick => 4_usize.pow(k as u32) + 3 * 2_usize.pow(k as u32 - 1) + 1,
+                GapSequence::Ciura if k < CIURA.len() => CIURA[k],
+                GapSequence::Ciura => (gaps[k - 1] as f64 * 2.25) as usize,
+                GapSequence::Tokuda => {
+                    let k = k as i32 + 1;
+                    ((9_f64.powi(k) - 4_f64.powi(k)) / (5. * 4_f64.powi(k - 1))).ceil() as usize
+                }
+            };
+            if gap >= len {
+                break;
+            }
+            gaps.push(gap);
+        }
+        gaps.reverse();
+        gaps
+    }
+}
+
+//experimentally found by Ciura, extended by multiplying with 2.25
+const CIURA: [usize; 9] = [1, 4, 10, 23, 57, 132, 301, 701, 1750];
+
+//insertion sort over gapped sub-sequences with shrinking gaps, the last gap is always 1
+#[derive(Clone)]
+pub struct ShellSort {
+    sequence: GapSequence,
+    gaps: Vec<usize>,
+    gap: usize,       //index of the current gap in gaps
+    main: usize,      //index of main ptr
+    insertion: usize, //index of insertion ptr
+    started: bool,
+}
+
+impl ShellSort {
+    pub fn new(sequence: GapSequence) -> Self {
+        ShellSort {
+            sequence,
+            gaps: vec![],
+            gap: 0,
+            main: 0,
+            insertion: 0,
+            started: false,
+        }
+    }
+
+    fn current_gap(&self) -> usize {
+        self.gaps.get(self.gap).copied().unwrap_or(1)
+    }
+}
+
+impl SortStepper for ShellSort {
+    fn step(&mut self, v: &mut Traced) -> bool {
+        if !self.started {
+            self.started = true;
+            self.gaps = self.sequence.gaps(v.len());
+            self.main = self.current_gap();
+            self.insertion = self.main;
+        }
+        if self.gap >= self.gaps.len() {
+            return true;
+        }
+        let gap = self.current_gap();
+        if self.main >= v.len() {
+            self.gap += 1;
+            self.main = self.current_gap();
+            self.insertion = self.main;
+        } else if self.insertion >= gap && v.compare(self.insertion - gap, self.insertion).is_gt() {
+            v.swap(self.insertion - gap, self.insertion);
+            self.insertion -= gap;
+        } else {
+            self.main += 1;
+            self.insertion = self.main;
+        }
+        false
+    }
+
+    fn color(&self, i: usize) -> Option<Color> {
+        if !self.started || self.gap >= self.gaps.len() {
+            return None;
+        }
+        let gap = self.current_gap();
+        if i == self.insertion {
+            Some(Color::RED)
+        } else if i <= self.main && i % gap == self.main % gap {
+            Some(Color::GREEN)
+        } else {
+            None
+        }
+    }
+
+    fn cursor(&self) -> usize {
+        self.insertion
+    }
+
+    fn status(&self) -> Option<String> {
+        Some(format!(
+            "Gap sequence: {:?}, gap {} ({} of {})",
+            self.sequence,
+            self.current_gap(),
+            (self.gap + 1).min(self.gaps.len()),
+            self.gaps.len()
+        ))
+    }
+}
+
+#[test]
+fn gap_sequence_test() {
+    //checking the start of each sequence against the published values
+    assert_eq!(GapSequence::Shell.gaps(100), vec![50, 25, 12, 6, 3, 1]);
+    assert_eq!(GapSequence::Shell.gaps(7), vec![3, 1]);
+    assert_eq!(GapSequence::Knuth.gaps(100), vec![40, 13, 4, 1]);
+    assert_eq!(GapSequence::Sedgewick.gaps(300), vec![281, 77, 23, 8, 1]);
+    assert_eq!(
+        GapSequence::Ciura.gaps(2000),
+        vec![1750, 701, 301, 132, 57, 23, 10, 4, 1]
+    );
+    assert_eq!(
+        GapSequence::Tokuda.gaps(600),
+        vec![525, 233, 103, 46, 20, 9, 4, 1]
+    );
+    for sequence in GapSequence::ALL {
+        assert_eq!(sequence.gaps(1), vec![1]);
+    }
+}
+
+#[test]
+fn shell_sort_test() {
+    //checking if shell sort is correct with every gap sequence for one random vec for each len 1-1000
+    use super::{is_sorted, run_to_end};
+    use rand::{thread_rng, Rng};
+
+    for sequence in GapSequence::ALL {
+        for len in 1..=1000 {
+            let mut v: Vec<i32> = (0..len).map(|_| thread_rng().gen::<i32>()).collect();
+            run_to_end(&mut ShellSort::new(sequence), &mut v);
+            assert!(is_sorted(&v));
+        }
+    }
+}
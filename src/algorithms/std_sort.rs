@@ -0,0 +1,263 @@
+use super::{SortStepper, Traced};
+use bevy::prelude::Color;
+use serde::{Deserialize, Serialize};
+use std::cell::{Cell, RefCell};
+use std::cmp::Ordering;
+use std::mem::size_of;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum StdSortKind {
+    Stable,
+    Unstable,
+    ByKey,
+}
+
+impl StdSortKind {
+    pub const ALL: [StdSortKind; 3] = [
+        StdSortKind::Stable,
+        StdSortKind::Unstable,
+        StdSortKind::ByKey,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            StdSortKind::Stable => "slice::sort",
+            StdSortKind::Unstable => "slice::sort_unstable",
+            StdSortKind::ByKey => "slice::sort_by_key",
+        }
+    }
+}
+
+//an element the std sort looked at: its index in the list, None while it is in a scratch buffer
+//or on the stack, and its id
+type Read = (Option<usize>, usize);
+
+//remembers every element the std sort reads, in order
+#[derive(Default, Clone)]
+struct Recorder {
+    base: Cell<usize>, //address of the list being sorted
+    len: usize,
+    reads: RefCell<Vec<Read>>,
+}
+
+impl Recorder {
+    //the std sorts move elements bitwise without calling into the element type,
+    //so where an element is gets recovered from the address it is compared at
+    fn read(&self, element: &Tracked) {
+        let offset = (element as *const Tracked as usize).wrapping_sub(self.base.get());
+        let size = size_of::<Tracked>();
+        let index = (offset % size == 0 && offset / size < self.len).then_some(offset / size);
+        self.reads.borrow_mut().push((index, element.id));
+    }
+}
+
+//list element that records every time it is compared
+struct Tracked<'a> {
+    value: i32, //what the element is sorted by, the key of a record
+    id: usize,
+    recorder: &'a Recorder,
+}
+
+impl Tracked<'_> {
+    fn key(&self) -> i32 {
+        self.recorder.read(self);
+        self.value
+    }
+}
+
+impl PartialEq for Tracked<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Tracked<'_> {}
+
+impl PartialOrd for Tracked<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Tracked<'_> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key().cmp(&other.key())
+    }
+}
+
+//runs the real std sort on a copy of the list and then replays it one comparison per operation
+//a comparison also writes the compared elements to where the sort had moved them by then,
+//anything still out of place once the comparisons run out is written in a last pass
+#[derive(Clone)]
+pub struct StdSort {
+    kind: StdSortKind,
+    values: Vec<i32>,            //value of every element by id
+    comparisons: Vec<[Read; 2]>, //recorded comparisons
+    sorted: Vec<i32>,            //result of the std sort
+    shown: Vec<i32>,             //the list as the replay has written it so far
+    next: usize,                 //next comparison to replay
+    write: usize,                //next position of the last pass
+    started: bool,
+}
+
+impl StdSort {
+    pub fn new(kind: StdSortKind) -> Self {
+        StdSort {
+            kind,
+            values: vec![],
+            comparisons: vec![],
+            sorted: vec![],
+            shown: vec![],
+            next: 0,
+            write: 0,
+            started: false,
+        }
+    }
+
+    fn record(&mut self, v: &mut Traced) {
+        let recorder = Recorder {
+            len: v.len(),
+            ..Default::default()
+        };
+        self.values = v.read_all();
+        self.shown = self.values.clone();
+        let mut tracked: Vec<Tracked> = self
+            .values
+            .iter()
+            .enumerate()
+            .map(|(id, &value)| Tracked {
+                value: v.key(value),
+                id,
+                recorder: &recorder,
+            })
+            .collect();
+        recorder.base.set(tracked.as_ptr() as usize);
+        match self.kind {
+            StdSortKind::Stable => tracked.sort(),
+            StdSortKind::Unstable => tracked.sort_unstable(),
+            StdSortKind::ByKey => tracked.sort_by_key(Tracked::key),
+        }
+        self.sorted = tracked
+            .iter()
+            .map(|element| self.values[element.id])
+            .collect();
+        //every comparison reads both of its elements
+        let reads = recorder.reads.take();
+        self.comparisons = reads.chunks_exact(2).map(|c| [c[0], c[1]]).collect();
+    }
+
+    fn replaying(&self) -> bool {
+        self.next <= self.comparisons.len()
+    }
+
+    //comparison replayed by the last operation
+    fn last(&self) -> Option<&[Read; 2]> {
+        self.comparisons.get(self.next.checked_sub(1)?)
+    }
+}
+
+impl SortStepper for StdSort {
+    fn step(&mut self, v: &mut Traced) -> bool {
+        if !self.started {
+            self.started = true;
+            self.record(v);
+        }
+        if self.next < self.comparisons.len() {
+            let [a, b] = self.comparisons[self.next];
+            for (index, id) in [a, b] {
+                match index {
+                    Some(i) if self.shown[i] != self.values[id] => {
+                        v.set(i, self.values[id]);
+                        self.shown[i] = self.values[id];
+                    }
+                    _ => {}
+                }
+            }
+            match (a, b) {
+                ((Some(i), _), (Some(j), _)) => v.compare(i, j),
+                ((_, x), (_, y)) => v.compare_aux(self.values[x], self.values[y]),
+            };
+            self.next += 1;
+            return false;
+        }
+        //one step with nothing highlighted between the replay and the last pass
+        if self.replaying() {
+            self.next += 1;
+        }
+        while self.write < v.len() && self.shown[self.write] == self.sorted[self.write] {
+            self.write += 1;
+        }
+        if self.write >= v.len() {
+            return true;
+        }
+        v.set(self.write, self.sorted[self.write]);
+        self.shown[self.write] = self.sorted[self.write];
+        false
+    }
+
+    fn color(&self, i: usize) -> Option<Color> {
+        if !self.started {
+            return None;
+        }
+        if !self.replaying() {
+            return match i.cmp(&self.write) {
+                Ordering::Less => Some(Color::GREEN),
+                Ordering::Equal => Some(Color::RED),
+                Ordering::Greater => None,
+            };
+        }
+        let [a, b] = self.last()?;
+        if a.0 == Some(i) {
+            Some(Color::RED)
+        } else if b.0 == Some(i) {
+            Some(Color::BLUE)
+        } else {
+            None
+        }
+    }
+
+    fn cursor(&self) -> usize {
+        if !self.replaying() {
+            return self.write;
+        }
+        self.last().and_then(|[a, b]| a.0.or(b.0)).unwrap_or(0)
+    }
+
+    fn status(&self) -> Option<String> {
+        if !self.started {
+            return None;
+        }
+        let mut status = format!(
+            "{}, comparison {} of {}",
+            self.kind.name(),
+            self.next.min(self.comparisons.len()),
+            self.comparisons.len()
+        );
+        if self
+            .last()
+            .is_some_and(|[a, b]| a.0.is_none() || b.0.is_none())
+        {
+            status.push_str(", comparing outside the list");
+        }
+        Some(status)
+    }
+}
+
+#[test]
+fn std_sort_test() {
+    //checking if every replayed std sort is correct for one random vec for each len 1-1000
+    use super::{is_sorted, run_to_end};
+    use rand::{thread_rng, Rng};
+
+    for kind in StdSortKind::ALL {
+        for len in 1..=1000 {
+            let mut v: Vec<i32> = (0..len).map(|_| thread_rng().gen_range(0..len)).collect();
+            let mut stepper = StdSort::new(kind);
+            run_to_end(&mut stepper, &mut v);
+            assert!(is_sorted(&v));
+            //some comparisons have to be found inside the list for the replay to show anything
+            let inside = stepper.comparisons.iter().flatten();
+            assert!(len < 2 || inside.filter(|(index, _)| index.is_some()).count() > 0);
+        }
+    }
+}
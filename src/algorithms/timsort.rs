@@ -0,0 +1,378 @@
+use super::merge::Merging;
+use super::{palette, SortStepper, Traced};
+use bevy::prelude::Color;
+use std::cmp::{min, Ordering};
+
+//a sorted run of the list, the id picks its color
+#[derive(Clone, Copy)]
+struct Run {
+    start: usize,
+    len: usize,
+    id: usize,
+}
+
+impl Run {
+    fn contains(&self, i: usize) -> bool {
+        i >= self.start && i < self.start + self.len
+    }
+}
+
+//finds the longest run starting at lo, one comparison per operation
+//descending runs are reversed afterwards, one swap per operation, so they have to be
+//strictly descending to keep equal elements in order
+#[derive(Clone)]
+struct RunScan {
+    lo: usize,
+    hi: usize, //end of the run found so far
+    descending: Option<bool>,
+    reverse: Option<(usize, usize)>, //part of the run that is left to reverse
+    done: bool,
+}
+
+impl RunScan {
+    fn new(lo: usize) -> Self {
+        RunScan {
+            lo,
+            hi: lo + 1,
+            descending: None,
+            reverse: None,
+            done: false,
+        }
+    }
+
+    //returns true once lo..hi is an ascending run
+    fn step(&mut self, v: &mut Traced) -> bool {
+        if let Some((a, b)) = self.reverse {
+            if b - a >= 2 {
+                v.swap(a, b - 1);
+                self.reverse = Some((a + 1, b - 1));
+                return false;
+            }
+            self.reverse = None;
+            self.done = true;
+        }
+        if self.done {
+            return true;
+        }
+        if self.hi < v.len() {
+            let descending = v.compare(self.hi, self.hi - 1).is_lt();
+            if self.descending.is_none() || self.descending == Some(descending) {
+                self.descending = Some(descending);
+                self.hi += 1;
+                return false;
+            }
+        }
+        if self.descending == Some(true) {
+            self.reverse = Some((self.lo, self.hi));
+            return self.step(v);
+        }
+        self.done = true;
+        true
+    }
+
+    fn color(&self, i: usize, id: usize) -> Option<Color> {
+        match self.reverse {
+            Some((a, b)) if i == a || i + 1 == b => Some(Color::RED),
+            None if i == self.hi => Some(Color::RED),
+            _ if i >= self.lo && i < self.hi => Some(palette(id)),
+            _ => None,
+        }
+    }
+}
+
+//minimum run length for a list of length n, between 32 and 64 so the number of runs is close to a power of two
+fn minrun(mut n: usize) -> usize {
+    let mut r = 0;
+    while n >= 64 {
+        r |= n & 1;
+        n >>= 1;
+    }
+    n + r
+}
+
+//timsort without galloping: runs are found, extended to minrun with binary insertion
+//and merged whenever the run stack breaks its invariants
+#[derive(Default, Clone)]
+pub struct TimSort {
+    minrun: usize,
+    runs: Vec<Run>, //run stack
+    next: usize,    //start of the next run to find
+    next_id: usize,
+    scan: Option<RunScan>,
+    insert: Option<(usize, usize)>, //binary insertion moving the element at .0 down to .1
+    merging: Option<(Merging, usize)>, //merge being done and the id of the merged run
+    started: bool,
+}
+
+impl TimSort {
+    //index of the lower run of the next pair to merge, force merges down to a single run
+    fn merge_at(&self, force: bool) -> Option<usize> {
+        let n = self.runs.len();
+        if n < 2 {
+            return None;
+        }
+        let len = |i: usize| self.runs[i].len;
+        if force
+            || (n >= 3 && len(n - 3) <= len(n - 2) + len(n - 1))
+            || (n >= 4 && len(n - 4) <= len(n - 3) + len(n - 2))
+        {
+            if n >= 3 && len(n - 3) < len(n - 1) {
+                Some(n - 3)
+            } else {
+                Some(n - 2)
+            }
+        } else if len(n - 2) <= len(n - 1) {
+            Some(n - 2)
+        } else {
+            None
+        }
+    }
+}
+
+impl SortStepper for TimSort {
+    fn step(&mut self, v: &mut Traced) -> bool {
+        if !self.started {
+            self.started = true;
+            self.minrun = minrun(v.len());
+        }
+        loop {
+            if let Some((merging, _)) = &mut self.merging {
+                if !merging.step(v) {
+                    return false;
+                }
+                self.merging = None;
+            }
+            if let Some(scan) = &mut self.scan {
+                if !scan.step(v) {
+                    return false;
+                }
+                //short runs are extended to minrun by binary insertion
+                if let Some((ptr, pos)) = self.insert {
+                    if ptr > pos {
+                        v.swap(ptr - 1, ptr);
+                        self.insert = Some((ptr - 1, pos));
+                        return false;
+                    }
+                    self.insert = None;
+                    scan.hi += 1;
+                }
+                if scan.hi < min(scan.lo + self.minrun, v.len()) {
+                    let pos = v.partition_point(scan.lo, scan.hi, scan.hi, Ordering::is_le);
+                    self.insert = Some((scan.hi, pos));
+                    return false;
+                }
+                self.runs.push(Run {
+                    start: scan.lo,
+                    len: scan.hi - scan.lo,
+                    id: self.next_id,
+                });
+                self.next_id += 1;
+                self.next = scan.hi;
+                self.scan = None;
+            }
+            if let Some(a) = self.merge_at(self.next >= v.len()) {
+                let (low, high) = (self.runs[a], self.runs[a + 1]);
+                let merging = Merging::new(v, low.start, high.start, high.start + high.len);
+                self.merging = Some((merging, self.next_id));
+                self.runs[a] = Run {
+                    start: low.start,
+                    len: low.len + high.len,
+                    id: self.next_id,
+                };
+                self.runs.remove(a + 1);
+                self.next_id += 1;
+                continue;
+            }
+            if self.next < v.len() {
+                self.scan = Some(RunScan::new(self.next));
+                continue;
+            }
+            return true;
+        }
+    }
+
+    fn color(&self, i: usize) -> Option<Color> {
+        if let Some((merging, id)) = &self.merging {
+            if i == merging.cursor() {
+                return Some(Color::RED);
+            } else if i >= merging.lo && i < merging.hi() {
+                return Some(palette(*id));
+            }
+        }
+        if let Some(scan) = &self.scan {
+            match self.insert {
+                Some((ptr, _)) if i == ptr => return Some(Color::RED),
+                _ => {}
+            }
+            if let Some(color) = scan.color(i, self.next_id) {
+                return Some(color);
+            }
+        }
+        let run = self.runs.iter().find(|run| run.contains(i))?;
+        Some(palette(run.id))
+    }
+
+    fn cursor(&self) -> usize {
+        match (&self.merging, &self.scan) {
+            (Some((merging, _)), _) => merging.cursor(),
+            (_, Some(scan)) => scan.hi,
+            _ => 0,
+        }
+    }
+
+    fn status(&self) -> Option<String> {
+        let lens: Vec<String> = self.runs.iter().map(|run| run.len.to_string()).collect();
+        Some(format!(
+            "Minrun {}, run stack [{}]",
+            self.minrun,
+            lens.join(", ")
+        ))
+    }
+}
+
+//finds all runs first and then merges neighbouring runs pairwise until one run is left
+#[derive(Default, Clone)]
+pub struct NaturalMergeSort {
+    runs: Vec<Run>,   //runs of the current pass
+    merged: Vec<Run>, //runs already produced by the current pass
+    pair: usize,      //index of the next pair of runs to merge
+    next: usize,      //start of the next run to find
+    next_id: usize,
+    scan: Option<RunScan>,
+    merging: Option<(Merging, usize)>, //merge being done and the id of the merged run
+    scanned: bool,
+}
+
+impl SortStepper for NaturalMergeSort {
+    fn step(&mut self, v: &mut Traced) -> bool {
+        loop {
+            if let Some((merging, _)) = &mut self.merging {
+                if !merging.step(v) {
+                    return false;
+                }
+                self.merging = None;
+            }
+            if !self.scanned {
+                if let Some(scan) = &mut self.scan {
+                    if !scan.step(v) {
+                        return false;
+                    }
+                    self.runs.push(Run {
+                        start: scan.lo,
+                        len: scan.hi - scan.lo,
+                        id: self.next_id,
+                    });
+                    self.next_id += 1;
+                    self.next = scan.hi;
+                    self.scan = None;
+                }
+                if self.next < v.len() {
+                    self.scan = Some(RunScan::new(self.next));
+                } else {
+                    self.scanned = true;
+                }
+                continue;
+            }
+            if self.pair >= self.runs.len() {
+                self.runs = std::mem::take(&mut self.merged);
+                self.pair = 0;
+            }
+            if self.pair == 0 && self.runs.len() <= 1 {
+                return true;
+            }
+            if self.pair + 1 < self.runs.len() {
+                let (low, high) = (self.runs[self.pair], self.runs[self.pair + 1]);
+                let merging = Merging::new(v, low.start, high.start, high.start + high.len);
+                self.merging = Some((merging, self.next_id));
+                self.merged.push(Run {
+                    start: low.start,
+                    len: low.len + high.len,
+                    id: self.next_id,
+                });
+                self.next_id += 1;
+                self.pair += 2;
+            } else {
+                self.merged.push(self.runs[self.pair]);
+                self.pair += 1;
+            }
+        }
+    }
+
+    fn color(&self, i: usize) -> Option<Color> {
+        if let Some((merging, id)) = &self.merging {
+            if i == merging.cursor() {
+                return Some(Color::RED);
+            } else if i >= merging.lo && i < merging.hi() {
+                return Some(palette(*id));
+            }
+        }
+        if let Some(color) = self
+            .scan
+            .as_ref()
+            .and_then(|scan| scan.color(i, self.next_id))
+        {
+            return Some(color);
+        }
+        let pending = self.runs.iter().skip(self.pair);
+        let run = self
+            .merged
+            .iter()
+            .chain(pending)
+            .find(|run| run.contains(i))?;
+        Some(palette(run.id))
+    }
+
+    fn cursor(&self) -> usize {
+        match (&self.merging, &self.scan) {
+            (Some((merging, _)), _) => merging.cursor(),
+            (_, Some(scan)) => scan.hi,
+            _ => 0,
+        }
+    }
+
+    fn status(&self) -> Option<String> {
+        if self.scanned {
+            Some(format!(
+                "{} runs left to merge",
+                self.runs.len() - self.pair + self.merged.len()
+            ))
+        } else {
+            Some(format!("{} runs found", self.runs.len()))
+        }
+    }
+}
+
+#[test]
+fn minrun_test() {
+    //checking that minrun keeps short lists whole and splits longer ones into runs of 32 to 64
+    assert_eq!(minrun(63), 63);
+    assert_eq!(minrun(64), 32);
+    assert_eq!(minrun(65), 33);
+    assert_eq!(minrun(1000), 63);
+}
+
+#[test]
+fn timsort_test() {
+    //checking if timsort and natural merge sort are correct for one partially sorted vec for each len 1-1000
+    use super::{is_sorted, run_to_end};
+    use rand::{thread_rng, Rng};
+
+    for len in 1..=1000 {
+        let mut v: Vec<i32> = (0..len)
+            .map(|_| thread_rng().gen_range(0..len / 2 + 1))
+            .collect();
+        //sorted and reversed stretches so there are runs to find
+        let a = thread_rng().gen_range(0..len as usize);
+        let b = thread_rng().gen_range(a..len as usize);
+        v[a..b].sort();
+        let c = thread_rng().gen_range(0..len as usize);
+        let d = thread_rng().gen_range(c..len as usize);
+        v[c..d].sort_by(|x, y| y.cmp(x));
+
+        let mut tim = v.clone();
+        run_to_end(&mut TimSort::default(), &mut tim);
+        assert!(is_sorted(&tim));
+        run_to_end(&mut NaturalMergeSort::default(), &mut v);
+        assert!(is_sorted(&v));
+    }
+}
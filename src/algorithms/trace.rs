@@ -0,0 +1,632 @@
+use super::adversary::Adversary;
+use super::{record_key, Algorithm, SortSettings, SortStepper};
+use bevy::prelude::Color;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::ops::Range;
+
+//a single access of an algorithm to the list, indices are into the whole list
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Event {
+    Compare(usize, usize),
+    Swap(usize, usize),
+    Write(usize, i32, i32), //index, old value, new value
+    Read(usize),
+    CompareAux, //comparison of two values kept outside the list
+    WriteAux,   //write into auxiliary memory like a buffer, a bucket or a count
+}
+
+impl Event {
+    fn redo(self, v: &mut [i32]) {
+        match self {
+            Event::Swap(a, b) => v.swap(a, b),
+            Event::Write(i, _, x) => v[i] = x,
+            _ => {}
+        }
+    }
+
+    fn undo(self, v: &mut [i32]) {
+        match self {
+            Event::Swap(a, b) => v.swap(a, b),
+            Event::Write(i, old, _) => v[i] = old,
+            _ => {}
+        }
+    }
+
+    //color of bar i if this event touched it
+    fn color(self, i: usize) -> Option<Color> {
+        match self {
+            Event::Swap(a, b) if i == a || i == b => Some(Color::RED),
+            Event::Write(j, ..) if i == j => Some(Color::RED),
+            Event::Compare(a, _) if i == a => Some(Color::RED),
+            Event::Compare(_, b) if i == b => Some(Color::BLUE),
+            Event::Read(j) if i == j => Some(Color::ORANGE),
+            _ => None,
+        }
+    }
+
+    fn index(self) -> Option<usize> {
+        match self {
+            Event::Compare(i, _) | Event::Swap(i, _) | Event::Write(i, ..) | Event::Read(i) => {
+                Some(i)
+            }
+            Event::CompareAux | Event::WriteAux => None,
+        }
+    }
+}
+
+//work done by an algorithm, counted from its events
+#[derive(Default, Clone, Copy, Debug, PartialEq)]
+pub struct Counters {
+    pub comparisons: usize,
+    pub swaps: usize,
+    pub writes: usize,
+    pub aux_writes: usize,
+    pub reads: usize,
+}
+
+impl Counters {
+    fn counter(&mut self, event: Event) -> &mut usize {
+        match event {
+            Event::Compare(..) | Event::CompareAux => &mut self.comparisons,
+            Event::Swap(..) => &mut self.swaps,
+            Event::Write(..) => &mut self.writes,
+            Event::WriteAux => &mut self.aux_writes,
+            Event::Read(_) => &mut self.reads,
+        }
+    }
+
+    pub(super) fn add(&mut self, event: Event) {
+        *self.counter(event) += 1;
+    }
+
+    fn remove(&mut self, event: Event) {
+        *self.counter(event) -= 1;
+    }
+}
+
+//the list as the algorithms see it, every access goes through here and is recorded as an event
+pub struct Traced<'a> {
+    v: &'a mut [i32],
+    offset: usize, //index of v in the whole list
+    events: &'a mut Vec<Event>,
+    adversary: Option<&'a mut Adversary>, //decides comparisons instead of the values
+    records: bool,                        //elements are records and only their keys are compared
+}
+
+impl<'a> Traced<'a> {
+    pub fn new(v: &'a mut [i32], events: &'a mut Vec<Event>) -> Self {
+        Traced {
+            v,
+            offset: 0,
+            events,
+            adversary: None,
+            records: false,
+        }
+    }
+
+    pub fn with_records(mut self, records: bool) -> Self {
+        self.records = records;
+        self
+    }
+
+    //what an element is sorted by, the key of a record or the value itself
+    pub fn key(&self, x: i32) -> i32 {
+        if self.records {
+            record_key(x)
+        } else {
+            x
+        }
+    }
+
+    //view whose comparisons are answered by the adversary, the values are only ids
+    pub(super) fn with_adversary(
+        v: &'a mut [i32],
+        events: &'a mut Vec<Event>,
+        adversary: &'a mut Adversary,
+    ) -> Self {
+        Traced {
+            v,
+            offset: 0,
+            events,
+            adversary: Some(adversary),
+            records: false,
+        }
+    }
+
+    fn order(&mut self, a: i32, b: i32) -> Ordering {
+        match &mut self.adversary {
+            Some(adversary) => adversary.compare(a, b),
+            None => self.key(a).cmp(&self.key(b)),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.v.len()
+    }
+
+    pub fn get(&mut self, i: usize) -> i32 {
+        self.events.push(Event::Read(self.offset + i));
+        self.v[i]
+    }
+
+    //copy of the whole list, reading every element
+    pub fn read_all(&mut self) -> Vec<i32> {
+        (0..self.len()).map(|i| self.get(i)).collect()
+    }
+
+    //reads element i and stores it in auxiliary memory
+    pub fn take(&mut self, i: usize) -> i32 {
+        self.write_aux();
+        self.get(i)
+    }
+
+    pub fn write_aux(&mut self) {
+        self.events.push(Event::WriteAux);
+    }
+
+    //compares two values the algorithm keeps outside the list
+    pub fn compare_aux(&mut self, a: i32, b: i32) -> Ordering {
+        self.events.push(Event::CompareAux);
+        self.order(a, b)
+    }
+
+    pub fn compare(&mut self, a: usize, b: usize) -> Ordering {
+        self.events
+            .push(Event::Compare(self.offset + a, self.offset + b));
+        self.order(self.v[a], self.v[b])
+    }
+
+    pub fn swap(&mut self, a: usize, b: usize) {
+        self.events
+            .push(Event::Swap(self.offset + a, self.offset + b));
+        self.v.swap(a, b);
+    }
+
+    pub fn set(&mut self, i: usize, x: i32) {
+        self.events
+            .push(Event::Write(self.offset + i, self.v[i], x));
+        self.v[i] = x;
+    }
+
+    //view of lo..hi, events keep the indices of the whole list
+    pub fn slice(&mut self, lo: usize, hi: usize) -> Traced<'_> {
+        Traced {
+            v: &mut self.v[lo..hi],
+            offset: self.offset + lo,
+            events: self.events,
+            adversary: self.adversary.as_deref_mut(),
+            records: self.records,
+        }
+    }
+
+    //binary search for the first index in lo..hi whose order against element key does not match pred
+    pub fn partition_point(
+        &mut self,
+        lo: usize,
+        hi: usize,
+        key: usize,
+        pred: impl Fn(Ordering) -> bool,
+    ) -> usize {
+        let (mut lo, mut hi) = (lo, hi);
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            if pred(self.compare(mid, key)) {
+                lo = mid + 1;
+            } else {
+                hi = mid;
+            }
+        }
+        lo
+    }
+}
+
+//everything an algorithm did to a list, grouped into operations
+//the list can be moved back and forth through the recorded operations, the algorithm
+//only continues once the list is back at the end of the recording
+#[derive(Default, Clone)]
+pub struct Trace {
+    pub initial: Vec<i32>,
+    pub events: Vec<Event>,
+    ends: Vec<usize>,   //index in events where every operation ends
+    position: usize,    //operations applied to the list
+    counters: Counters, //work done up to position
+    truncated: bool,
+    pub records: bool, //the list holds records, see records.rs
+}
+
+impl Trace {
+    //most events kept, a longer run is not recorded so an endless sort cannot eat all memory
+    pub const LIMIT: usize = 10_000_000;
+
+    pub fn new(initial: Vec<i32>) -> Self {
+        Trace {
+            initial,
+            ..Default::default()
+        }
+    }
+
+    //trace of a run loaded from somewhere else, with the list at its start
+    pub fn from_operations(initial: Vec<i32>, operations: Vec<Vec<Event>>) -> Self {
+        let mut trace = Trace::new(initial);
+        for operation in operations {
+            trace.events.extend(operation);
+            trace.ends.push(trace.events.len());
+        }
+        trace
+    }
+
+    //performs one operation of the stepper on the list and records it,
+    //or redoes the next recorded one if the list has been rewound
+    pub fn step(&mut self, stepper: &mut dyn SortStepper, v: &mut [i32]) -> bool {
+        if self.rewound() {
+            self.redo(v);
+            return false;
+        }
+        let start = self.events.len();
+        let mut traced = Traced::new(v, &mut self.events).with_records(self.records);
+        let done = stepper.step(&mut traced);
+        for &event in &self.events[start..] {
+            self.counters.add(event);
+        }
+        if self.truncated || self.events.len() > Self::LIMIT {
+            self.truncated = true;
+            self.events = vec![];
+            self.ends = vec![];
+        } else if !done || self.events.len() > start {
+            self.ends.push(self.events.len());
+        }
+        self.position = self.operations();
+        done
+    }
+
+    //undoes the last applied operation
+    pub fn undo(&mut self, v: &mut [i32]) {
+        let Some(position) = self.position.checked_sub(1) else {
+            return;
+        };
+        for &event in self.events[self.range(position)].iter().rev() {
+            event.undo(v);
+            self.counters.remove(event);
+        }
+        self.position = position;
+    }
+
+    fn redo(&mut self, v: &mut [i32]) {
+        for &event in &self.events[self.range(self.position)] {
+            event.redo(v);
+            self.counters.add(event);
+        }
+        self.position += 1;
+    }
+
+    //moves the list to the state after the given number of operations
+    pub fn seek(&mut self, v: &mut [i32], position: usize) {
+        let position = position.min(self.operations());
+        while self.position > position {
+            self.undo(v);
+        }
+        while self.position < position {
+            self.redo(v);
+        }
+    }
+
+    pub fn counters(&self) -> Counters {
+        self.counters
+    }
+
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    //whether the list is behind the end of the recording
+    pub fn rewound(&self) -> bool {
+        self.position < self.operations()
+    }
+
+    //color of bar i from the last applied operation
+    pub fn color(&self, i: usize) -> Option<Color> {
+        self.operation_color(self.position.checked_sub(1)?, i)
+    }
+
+    //index touched last by the last applied operation
+    pub fn cursor(&self) -> usize {
+        self.operation_cursor(self.position.checked_sub(1))
+    }
+
+    //later events of an operation win
+    fn operation_color(&self, op: usize, i: usize) -> Option<Color> {
+        let events = self.operation(op).iter().rev();
+        events.copied().find_map(|event| event.color(i))
+    }
+
+    fn operation_cursor(&self, op: Option<usize>) -> usize {
+        let events = op.map_or(&[][..], |op| self.operation(op));
+        events
+            .iter()
+            .rev()
+            .find_map(|event| event.index())
+            .unwrap_or(0)
+    }
+
+    pub fn operations(&self) -> usize {
+        self.ends.len()
+    }
+
+    //events of the i-th operation
+    pub fn operation(&self, i: usize) -> &[Event] {
+        &self.events[self.range(i)]
+    }
+
+    //indices of the events of operation i
+    fn range(&self, i: usize) -> Range<usize> {
+        let start = if i == 0 { 0 } else { self.ends[i - 1] };
+        start..self.ends[i]
+    }
+
+    //whether the run was too long to be kept
+    pub fn truncated(&self) -> bool {
+        self.truncated
+    }
+}
+
+//the algorithm of a trace run again up to the position the list was rewound to, so its pointers,
+//ranges, buckets and heap can be shown as they were there, steppers are deterministic given the settings
+#[derive(Default)]
+pub struct Rewind {
+    stepper: Option<Box<dyn SortStepper>>,
+    v: Vec<i32>,                                        //list the stepper runs on
+    position: usize,                                    //operations the stepper has done
+    checkpoints: Vec<(Vec<i32>, Box<dyn SortStepper>)>, //list and stepper every spacing operations
+    spacing: usize,
+    matches: bool, //the stepper left the list the trace has at the position
+}
+
+impl Rewind {
+    //most snapshots kept of one run, so going back never runs more than a slice of it again
+    const CHECKPOINTS: usize = 256;
+
+    //brings the stepper to the position of the trace, going back to the last checkpoint before it
+    //v is the list at the position, an imported trace can disagree with its algorithm
+    pub fn seek(
+        &mut self,
+        trace: &Trace,
+        v: &[i32],
+        algorithm: Algorithm,
+        settings: &SortSettings,
+    ) {
+        let position = trace.position();
+        if self.stepper.is_none() {
+            self.stepper = Some(algorithm.stepper(settings));
+            self.v = trace.initial.clone();
+            self.position = 0;
+            self.checkpoints.clear();
+            self.spacing = trace.operations().div_ceil(Self::CHECKPOINTS).max(1);
+        }
+        if self.position > position {
+            let (v, stepper) = &self.checkpoints[position / self.spacing];
+            self.v = v.clone();
+            self.stepper = Some(stepper.clone_stepper());
+            self.position = position / self.spacing * self.spacing;
+        }
+        let Some(stepper) = self.stepper.as_mut() else {
+            return;
+        };
+        let mut events = vec![];
+        while self.position < position {
+            if self.position % self.spacing == 0
+                && self.checkpoints.len() == self.position / self.spacing
+            {
+                self.checkpoints
+                    .push((self.v.clone(), stepper.clone_stepper()));
+            }
+            stepper.step(&mut Traced::new(&mut self.v, &mut events).with_records(trace.records));
+            events.clear();
+            self.position += 1;
+        }
+        self.matches = self.v == v;
+    }
+
+    pub fn clear(&mut self) {
+        self.stepper = None;
+        self.checkpoints.clear();
+    }
+
+    //None once the run no longer follows the trace, the trace's own colors and cursor are shown then
+    pub fn stepper(&self) -> Option<&dyn SortStepper> {
+        self.stepper.as_deref().filter(|_| self.matches)
+    }
+}
+
+//animates a recorded trace one operation at a time without running the algorithm again
+#[derive(Clone)]
+pub struct Replay {
+    trace: Trace,
+    next: usize, //next operation to replay
+}
+
+impl Replay {
+    //the list has to be reset to trace.initial before replaying
+    pub fn new(trace: Trace) -> Self {
+        Replay { trace, next: 0 }
+    }
+}
+
+impl SortStepper for Replay {
+    fn step(&mut self, v: &mut Traced) -> bool {
+        if self.next >= self.trace.operations() {
+            return true;
+        }
+        //events go through the list again so a replay records the same trace
+        for &event in self.trace.operation(self.next) {
+            match event {
+                Event::Compare(a, b) => {
+                    v.compare(a, b);
+                }
+                Event::Swap(a, b) => v.swap(a, b),
+                Event::Write(i, _, x) => v.set(i, x),
+                Event::Read(i) => {
+                    v.get(i);
+                }
+                Event::CompareAux | Event::WriteAux => v.events.push(event),
+            }
+        }
+        self.next += 1;
+        false
+    }
+
+    fn color(&self, i: usize) -> Option<Color> {
+        self.trace.operation_color(self.next.checked_sub(1)?, i)
+    }
+
+    fn cursor(&self) -> usize {
+        self.trace.operation_cursor(self.next.checked_sub(1))
+    }
+
+    fn status(&self) -> Option<String> {
+        Some(format!(
+            "Replaying operation {} of {}",
+            self.next,
+            self.trace.operations()
+        ))
+    }
+
+    fn mode(&self) -> Option<&str> {
+        Some("replay")
+    }
+}
+
+#[test]
+fn replay_test() {
+    //checking that replaying a recorded trace ends in the same list and records the same events
+    use super::{is_sorted, Algorithm, SortSettings};
+    use rand::{thread_rng, Rng};
+
+    for algorithm in Algorithm::ALL
+        .into_iter()
+        .filter(|a| *a != Algorithm::Schrödinger)
+    {
+        let initial: Vec<i32> = (0..100).map(|_| thread_rng().gen_range(0..100)).collect();
+        let mut stepper = algorithm.stepper(&SortSettings::default());
+        let mut trace = Trace::new(initial.clone());
+        let mut v = initial.clone();
+        while !trace.step(stepper.as_mut(), &mut v) {}
+        assert!(is_sorted(&v));
+
+        let mut replayed = Trace::new(initial.clone());
+        let mut replay = Replay::new(trace.clone());
+        let mut w = initial;
+        while !replayed.step(&mut replay, &mut w) {}
+        assert_eq!(v, w);
+        assert_eq!(trace.events, replayed.events);
+        assert_eq!(trace.operations(), replayed.operations());
+    }
+}
+
+#[test]
+fn rewind_test() {
+    //checking that seeking back and forth through a run restores the list and that a rewound run still ends sorted
+    use super::{is_sorted, Algorithm, SortSettings};
+    use rand::{thread_rng, Rng};
+
+    for algorithm in [
+        Algorithm::Quick,
+        Algorithm::Merge,
+        Algorithm::Radix,
+        Algorithm::Heap,
+    ] {
+        let initial: Vec<i32> = (0..100).map(|_| thread_rng().gen_range(0..100)).collect();
+        let mut stepper = algorithm.stepper(&SortSettings::default());
+        let mut trace = Trace::new(initial.clone());
+        let mut v = initial.clone();
+        let mut states = vec![v.clone()];
+        for _ in 0..200 {
+            trace.step(stepper.as_mut(), &mut v);
+            states.push(v.clone());
+        }
+        for _ in 0..100 {
+            let position = thread_rng().gen_range(0..=trace.operations());
+            trace.seek(&mut v, position);
+            assert_eq!(v, states[position]);
+        }
+        trace.seek(&mut v, 0);
+        assert_eq!(v, initial);
+        while !trace.step(stepper.as_mut(), &mut v) {}
+        assert!(is_sorted(&v));
+    }
+}
+
+#[test]
+fn rewind_state_test() {
+    //checking that a rewound run shows the colors, cursor and buckets the algorithm had at that operation
+    use rand::{thread_rng, Rng};
+
+    let settings = SortSettings::default();
+    for algorithm in [Algorithm::Quick, Algorithm::Radix, Algorithm::Heap] {
+        let initial: Vec<i32> = (0..60).map(|_| thread_rng().gen_range(0..100)).collect();
+        let mut stepper = algorithm.stepper(&settings);
+        let mut trace = Trace::new(initial.clone());
+        let mut v = initial;
+        let state = |stepper: &dyn SortStepper| {
+            let colors: Vec<_> = (0..60).map(|i| stepper.color(i)).collect();
+            (
+                colors,
+                stepper.cursor(),
+                stepper.buckets(),
+                stepper.heap_size(),
+            )
+        };
+        let mut states = vec![state(stepper.as_ref())];
+        while !trace.step(stepper.as_mut(), &mut v) {
+            states.push(state(stepper.as_ref()));
+        }
+        let mut rewind = Rewind::default();
+        for _ in 0..50 {
+            let position = thread_rng().gen_range(0..trace.operations());
+            trace.seek(&mut v, position);
+            rewind.seek(&trace, &v, algorithm, &settings);
+            assert_eq!(state(rewind.stepper().unwrap()), states[position]);
+        }
+    }
+
+    //a trace imported with another algorithm is not followed by its rerun
+    let initial: Vec<i32> = (0..60).rev().collect();
+    let mut stepper = Algorithm::Quick.stepper(&settings);
+    let mut trace = Trace::new(initial.clone());
+    let mut v = initial;
+    while !trace.step(stepper.as_mut(), &mut v) {}
+    let mut rewind = Rewind::default();
+    trace.seek(&mut v, 0);
+    rewind.seek(&trace, &v, Algorithm::Bubble, &settings);
+    assert!(rewind.stepper().is_some());
+    trace.seek(&mut v, 10);
+    rewind.seek(&trace, &v, Algorithm::Bubble, &settings);
+    assert!(rewind.stepper().is_none());
+}
+
+#[test]
+fn counters_test() {
+    //checking the counters against the known number of comparisons and swaps of simple sorts
+    use super::{InsertionSort, SelectionSort};
+
+    for n in 1..50 {
+        let reversed: Vec<i32> = (0..n).rev().collect();
+        let mut v = reversed.clone();
+        let mut trace = Trace::new(reversed.clone());
+        let mut insertion = InsertionSort::default();
+        while !trace.step(&mut insertion, &mut v) {}
+        //insertion sort compares and swaps every pair of a reversed list once
+        let pairs = (n * (n - 1) / 2) as usize;
+        assert_eq!(trace.counters().comparisons, pairs);
+        assert_eq!(trace.counters().swaps, pairs);
+
+        let mut v = reversed.clone();
+        let mut trace = Trace::new(reversed);
+        let mut selection = SelectionSort::default();
+        while !trace.step(&mut selection, &mut v) {}
+        //selection sort compares every pair whatever the order, and its first element with itself
+        assert_eq!(trace.counters().comparisons, pairs + 1);
+        trace.seek(&mut v, 0);
+        assert_eq!(trace.counters(), Counters::default());
+    }
+}
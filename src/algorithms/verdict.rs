@@ -0,0 +1,69 @@
+use super::records::{record_key, unstable_pairs};
+
+//what the validation pass found in the list an algorithm left behind
+#[derive(Debug, Clone, PartialEq)]
+pub struct Verdict {
+    pub unsorted: Vec<usize>, //indices i where the element at i - 1 is bigger than the one at i
+    pub permutation: bool,    //the list holds exactly the elements the run started with
+    pub unstable: Option<Vec<usize>>, //pairs of equal keys left swapped, only known for records
+}
+
+impl Verdict {
+    //checks the list against the one the run started from, records are ordered by their keys
+    pub fn of(initial: &[i32], v: &[i32], records: bool) -> Self {
+        let key = |x: i32| if records { record_key(x) } else { x };
+        let unsorted = (1..v.len())
+            .filter(|&i| key(v[i - 1]) > key(v[i]))
+            .collect();
+        let (mut before, mut after) = (initial.to_vec(), v.to_vec());
+        before.sort_unstable();
+        after.sort_unstable();
+        Verdict {
+            unsorted,
+            permutation: before == after,
+            unstable: records.then(|| unstable_pairs(v)),
+        }
+    }
+
+    pub fn passed(&self) -> bool {
+        self.unsorted.is_empty() && self.permutation
+    }
+
+    //whether element i belongs to a pair that is out of order or to equal keys that swapped
+    pub fn flagged(&self, i: usize) -> bool {
+        let pair = |pairs: &[usize]| pairs.contains(&i) || pairs.contains(&(i + 1));
+        pair(&self.unsorted) || self.unstable.as_deref().is_some_and(pair)
+    }
+}
+
+#[test]
+fn verdict_test() {
+    //checking that sorted permutations pass and that unsorted, changed and unstable lists are caught
+    use super::pack_records;
+
+    let initial = [3, 1, 2, 2];
+    let sorted = Verdict::of(&initial, &[1, 2, 2, 3], false);
+    assert!(sorted.passed());
+    assert_eq!(sorted.unstable, None);
+    assert!(!(0..4).any(|i| sorted.flagged(i)));
+
+    let unsorted = Verdict::of(&initial, &[1, 3, 2, 2], false);
+    assert!(!unsorted.passed());
+    assert!(unsorted.permutation);
+    assert_eq!(unsorted.unsorted, vec![2]);
+    assert!(unsorted.flagged(1) && unsorted.flagged(2) && !unsorted.flagged(3));
+
+    //a sorted list that lost an element to a duplicate is not a permutation
+    let changed = Verdict::of(&initial, &[1, 2, 2, 2], false);
+    assert!(changed.unsorted.is_empty());
+    assert!(!changed.passed());
+
+    let records = pack_records(&[2, 1, 2]).unwrap();
+    let stable = Verdict::of(&records, &[records[1], records[0], records[2]], true);
+    assert!(stable.passed());
+    assert_eq!(stable.unstable, Some(vec![]));
+    let unstable = Verdict::of(&records, &[records[1], records[2], records[0]], true);
+    assert!(unstable.passed());
+    assert_eq!(unstable.unstable, Some(vec![2]));
+    assert!(unstable.flagged(2) && !unstable.flagged(0));
+}
@@ -1,12 +1,23 @@
+#![allow(clippy::too_many_arguments)]
+
+mod algorithms;
+
+use algorithms::{
+    has_worst_case, pack_records, palette, parse_list, record, record_color, record_key,
+    record_position, repack_records, unpack_records, worst_case, Algorithm, CostChart, Counters,
+    Distribution, Metric, Race, Replay, Rewind, SavedRun, SortSettings, SortStepper, Trace,
+    Verdict, MAX_ITEMS, MAX_KEY,
+};
 use bevy::prelude::*;
 use bevy::sprite::Anchor;
 use bevy::window::WindowResolution;
-use bevy_egui::{egui, EguiContexts, EguiPlugin};
-use rand::seq::SliceRandom;
-use std::cmp::min;
+use bevy_egui::{egui, EguiClipboard, EguiContexts, EguiPlugin};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::fs;
+use std::path::Path;
 use std::thread;
-use std::time::Duration;
-use std::vec;
+use std::time::{Duration, Instant};
 use Algorithm::*;
 
 const DEFAULT_SCREEN_RESOLUTION: (f32, f32) = (1200., 800.);
@@ -26,13 +37,20 @@ fn main() {
         }))
         .add_plugins(EguiPlugin)
         .add_systems(Update, settings_widget)
+        .add_systems(Update, trace_widget)
+        .add_systems(Update, cost_widget)
+        .add_systems(Update, seed_title)
+        .add_systems(Update, (race_widget, run_race).chain())
         .add_systems(Startup, setup)
         .add_systems(Update, render_list)
+        .add_systems(Update, edit_list)
+        .add_systems(Update, render_heap_tree)
         .add_systems(Update, speed_controller)
-        .add_systems(Update, insertion_sort)
-        .add_systems(Update, selection_sort)
-        .add_systems(Update, merge_sort)
-        .add_systems(Update, schrödinger_sort)
+        .add_systems(Update, (sort_sound, run_sort).chain())
+        .add_systems(
+            Update,
+            rewind_stepper.after(settings_widget).after(run_sort),
+        )
         .add_systems(Update, end_animation)
         .insert_resource(SelectedAlgorithm(Insertion))
         .insert_resource(Operations(1))
@@ -42,26 +60,35 @@ fn main() {
         .insert_resource(NumberOfItems(100))
         .insert_resource(Delay(0))
         .insert_resource(SortingOngoing(false))
-        .insert_resource(InsertionStep((0, 0)))
-        .insert_resource(SelectionStep((0, 0, 0)))
-        .insert_resource(MergeStep((1, 0, 0, 0, vec![], vec![])))
+        .insert_resource(ActiveSort(Insertion.stepper(&SortSettings::default())))
+        .insert_resource(SortSettings {
+            seed: rand::random(),
+            ..default()
+        })
         .insert_resource(AnimationStep((0, 0, Insertion)))
         .insert_resource(Sort(false))
         .insert_resource(Paused(false))
         .insert_resource(Observed(true))
         .insert_resource(Sound(false))
+        .insert_resource(HeapTree(false))
+        .insert_resource(LastRun(
+            Trace::default(),
+            Insertion,
+            SortSettings::default(),
+            None,
+        ))
+        .insert_resource(Cost(
+            CostChart::default(),
+            Metric::Comparisons,
+            vec![Insertion, Merge, Quick],
+        ))
+        .insert_resource(Racing(None, false))
+        .insert_resource(Rewound(Rewind::default()))
+        .insert_resource(Generator(Distribution::Shuffled, 10))
+        .insert_resource(Import(String::new(), "list.txt".to_string(), None))
         .run();
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
-enum Algorithm {
-    Insertion,
-    Selection,
-    Merge,
-    Schrödinger,
-    Validation,
-}
-
 #[derive(Clone, Copy, PartialEq)]
 enum Speed {
     Max,
@@ -104,13 +131,7 @@ struct Sort(bool);
 struct Paused(bool);
 
 #[derive(Resource)]
-struct InsertionStep((usize, usize)); //(index of main ptr, index of insertion ptr)
-
-#[derive(Resource)]
-struct SelectionStep((usize, usize, usize)); //(index of main ptr, index of selection ptr, index of selected value)
-
-#[derive(Resource)]
-struct MergeStep((usize, usize, usize, usize, Vec<i32>, Vec<i32>)); //(size of merge, merge number, ptr in vec1, ptr in vec2, vec1, vec2)
+struct ActiveSort(Box<dyn SortStepper>); //state of the running algorithm
 
 #[derive(Resource)]
 struct AnimationStep((usize, u32, Algorithm)); //(index of main ptr, prev operations, prev selected algorithm)
@@ -124,6 +145,31 @@ struct PitchFrequency(f32);
 #[derive(Resource)]
 struct Sound(bool);
 
+#[derive(Resource)]
+struct HeapTree(bool); //whether the heap is drawn as a binary tree over the list
+
+//operations of the last run, the algorithm and settings that did them
+//and what validation found in the list it left, once validation has started
+#[derive(Resource)]
+struct LastRun(Trace, Algorithm, SortSettings, Option<Verdict>);
+
+#[derive(Resource)]
+struct Rewound(Rewind); //algorithm of the last run brought to the position the list was rewound to
+
+#[derive(Resource)]
+struct Cost(CostChart, Metric, Vec<Algorithm>); //measured costs, what is plotted and the algorithms picked for the chart
+
+#[derive(Resource)]
+struct Generator(Distribution, usize); //distribution the list is generated from, swaps of a nearly sorted list
+
+#[derive(Resource)]
+struct Import(String, String, Option<Result<usize, String>>); //typed list, path of a list file, outcome of the last import
+
+#[derive(Resource)]
+struct Racing(Option<Race>, bool); //race shown instead of the list, whether it is paused
+
+//bevy systems take at most 16 parameters, so the widget resources share a tuple
+#[allow(clippy::type_complexity)]
 fn settings_widget(
     mut contexts: EguiContexts,
     mut selected: ResMut<SelectedAlgorithm>,
@@ -135,31 +181,106 @@ fn settings_widget(
     mut delay: ResMut<Delay>,
     mut sorting: ResMut<SortingOngoing>,
     mut observed: ResMut<Observed>,
-    mut insertion_step: ResMut<InsertionStep>,
-    mut selection_step: ResMut<SelectionStep>,
-    mut merge_step: ResMut<MergeStep>,
+    mut active: ResMut<ActiveSort>,
+    mut settings: ResMut<SortSettings>,
     mut paused: ResMut<Paused>,
     mut sort: ResMut<Sort>,
-    mut sound: ResMut<Sound>,
+    (mut sound, mut heap_tree, mut generator, mut import, clipboard, rewound): (
+        ResMut<Sound>,
+        ResMut<HeapTree>,
+        ResMut<Generator>,
+        ResMut<Import>,
+        Res<EguiClipboard>,
+        Res<Rewound>,
+    ),
+    mut last_run: ResMut<LastRun>,
 ) {
     egui::Window::new("Controller").show(contexts.ctx_mut(), |ui| {
         if !sorting.0 {
-            ui.add(egui::Slider::new(&mut n.0, 1..=1000).text("Number of items"));
+            ui.add(egui::Slider::new(&mut n.0, 1..=MAX_ITEMS as i32).text("Number of items"));
             if n.0 as usize != v.0.len() {
-                v.0 = (1..=n.0).collect();
+                if let Ok(keys) = keyed((1..=n.0).collect(), settings.records) {
+                    v.0 = keys;
+                }
+            }
+            egui::ComboBox::from_label("Distribution")
+                .selected_text(format!("{:?}", generator.0))
+                .show_ui(ui, |ui| {
+                    for distribution in Distribution::ALL {
+                        let name = format!("{:?}", distribution);
+                        ui.selectable_value(&mut generator.0, distribution, name);
+                    }
+                });
+            if generator.0 == Distribution::NearlySorted {
+                ui.add(egui::Slider::new(&mut generator.1, 0..=100).text("Random swaps"));
+            }
+            //the same seed always generates the same list and the same random choices of the algorithms
+            ui.horizontal(|ui| {
+                ui.label("Seed");
+                ui.add(egui::DragValue::new(&mut settings.seed));
+                if ui.button("Random seed").clicked() {
+                    settings.seed = rand::random();
+                }
+            });
+            ui.horizontal(|ui| {
+                if ui.button("Generate").clicked() {
+                    let mut rng = StdRng::seed_from_u64(settings.seed);
+                    let keys = generator.0.generate(v.0.len(), generator.1, &mut rng);
+                    if let Ok(keys) = keyed(keys, settings.records) {
+                        v.0 = keys;
+                    }
+                }
+                //the worst case depends on the settings of the algorithm, so it is built on demand
+                let known = has_worst_case(selected.0);
+                let worst = egui::Button::new(format!("Worst case for {:?}", selected.0));
+                if ui
+                    .add_enabled(known, worst)
+                    .on_disabled_hover_text("No worst case input is known for this algorithm")
+                    .clicked()
+                {
+                    let worst = worst_case(selected.0, &settings, v.0.len());
+                    if let Some(Ok(worst)) = worst.map(|keys| keyed(keys, settings.records)) {
+                        v.0 = worst;
+                    }
+                }
+            });
+            ui.collapsing("Import list", |ui| {
+                if let Some(imported) = import_ui(ui, &mut import, &clipboard, settings.records) {
+                    //the number of items follows the list instead of regenerating it
+                    n.0 = imported.len() as i32;
+                    v.0 = imported;
+                }
+            });
+            //records pair every key with where it started, so the colors show whether equal keys keep their order
+            let packed = pack_records(&v.0);
+            let mut records = settings.records;
+            let checkbox = egui::Checkbox::new(&mut records, "Keyed records");
+            if ui
+                .add_enabled(settings.records || packed.is_some(), checkbox)
+                .on_disabled_hover_text(format!("Keys have to be within ±{}", MAX_KEY))
+                .changed()
+            {
+                settings.records = records;
+                v.0 = match packed {
+                    Some(packed) if records => packed,
+                    _ => unpack_records(&v.0),
+                };
             }
-            if ui.button("Shuffle").clicked() {
-                v.0.shuffle(&mut rand::thread_rng());
+            if settings.records {
+                ui.label("Few unique or duplicates give many equal keys");
             }
         }
         ui.checkbox(&mut sound.0, "Sound");
+        if selected.0 == Heap {
+            ui.checkbox(&mut heap_tree.0, "Heap tree");
+        }
         if selected.0 != Validation {
             ui.horizontal(|ui| {
                 ui.radio_value(&mut max_speed.0, Speed::Limited, "Limit Speed");
                 ui.radio_value(&mut max_speed.0, Speed::Max, "Max Speed");
             });
             if max_speed.0 == Speed::Max {
-                ui.label(format!("Note: Max Speed is 100.000 Operations/Frame"));
+                ui.label("Note: Max Speed is 100.000 Operations/Frame");
             } else {
                 ui.horizontal(|ui| {
                     ui.radio_value(&mut speed_limit.0, SpeedLimit::Low, "Low Speed");
@@ -171,7 +292,7 @@ fn settings_widget(
                     }
                     ui.add(egui::Slider::new(&mut operations.0, 0..=100).text("Operations/Frame"));
                     ui.add(egui::Slider::new(&mut delay.0, 0..=1000).text("Delay (ms)/Frame"));
-                    ui.label(format!("Note: Delay will cause FPS to drop"));
+                    ui.label("Note: Delay will cause FPS to drop");
                 } else if speed_limit.0 == SpeedLimit::High {
                     delay.0 = 0;
                     ui.add(
@@ -180,46 +301,89 @@ fn settings_widget(
                 }
             }
         } else {
-            ui.label(format!("Validating sort"));
+            ui.label("Validating sort");
+            counters_ui(ui, last_run.0.counters());
+            if let Some(verdict) = &last_run.3 {
+                verdict_ui(ui, verdict);
+            }
         }
 
         if sorting.0 && !observed.0 && selected.0 == Schrödinger {
             ui.label(format!("Currently running: {:?}", selected.0));
-            ui.label(format!(
-                "Until the list is observed, it is both sorted and unsorted"
-            ));
+            ui.label("Until the list is observed, it is both sorted and unsorted");
             if ui.button("Observe").clicked() {
                 observed.0 = true;
             }
         }
-        if paused.0 {
-            if ui.button("Run 1 frame").clicked() {
-                sort.0 = true;
-            }
+        if paused.0 && ui.button("Run 1 frame").clicked() {
+            sort.0 = true;
         }
         if !sorting.0 {
             egui::ComboBox::from_label("Sorting algorithm")
                 .selected_text(format!("{:?}", selected.0))
                 .show_ui(ui, |ui| {
-                    ui.selectable_value(&mut selected.0, Algorithm::Insertion, "Insertion");
-                    ui.selectable_value(&mut selected.0, Algorithm::Selection, "Selection");
-                    ui.selectable_value(&mut selected.0, Algorithm::Merge, "Merge");
-                    ui.selectable_value(&mut selected.0, Algorithm::Schrödinger, "Schrödinger");
+                    for algorithm in Algorithm::ALL {
+                        ui.selectable_value(&mut selected.0, algorithm, format!("{:?}", algorithm));
+                    }
                 });
-            if ui.button("Start algorithm").clicked() {
+            settings.ui(ui, selected.0);
+            let fits = selected.0.check(&v.0, settings.records);
+            if let Err(e) = &fits {
+                ui.colored_label(egui::Color32::RED, e);
+            }
+            if ui
+                .add_enabled(fits.is_ok(), egui::Button::new("Start algorithm"))
+                .clicked()
+            {
+                active.0 = selected.0.stepper(&settings);
+                //stability is judged against the order the run starts from
+                if settings.records {
+                    v.0 = repack_records(&v.0);
+                }
+                let mut trace = Trace::new(v.0.clone());
+                trace.records = settings.records;
+                *last_run = LastRun(trace, selected.0, *settings, None);
                 sorting.0 = true;
                 paused.0 = false;
                 if selected.0 == Schrödinger {
                     observed.0 = false;
                 }
             }
-        } else if observed.0 && selected.0 != Validation {
-            ui.label(format!("Currently running: {:?}", selected.0));
-            if selected.0 == Schrödinger {
-                if ui.button("Stop Observing").clicked() {
-                    observed.0 = false;
+            if last_run.0.counters() != Counters::default() {
+                ui.label(format!("Last run: {:?}", last_run.1));
+                counters_ui(ui, last_run.0.counters());
+                if let Some(verdict) = &last_run.3 {
+                    verdict_ui(ui, verdict);
                 }
             }
+            if last_run.0.truncated() {
+                ui.label("The last run was too long to be recorded");
+            } else if last_run.0.operations() > 0 && ui.button("Replay last run").clicked() {
+                settings.records = last_run.2.records;
+                replay(&mut last_run, &mut v, &mut n, &mut selected, &mut active);
+                sorting.0 = true;
+                paused.0 = false;
+            }
+        } else if observed.0 && selected.0 != Validation {
+            let shown = shown(&active, &rewound, &last_run);
+            match shown.and_then(|stepper| stepper.mode()) {
+                Some(mode) => ui.label(format!("Currently running: {:?} ({})", selected.0, mode)),
+                None => ui.label(format!("Currently running: {:?}", selected.0)),
+            };
+            if let Some(status) = shown.and_then(|stepper| stepper.status()) {
+                ui.label(status);
+            }
+            counters_ui(ui, last_run.0.counters());
+            if last_run.0.rewound() {
+                ui.label(format!(
+                    "Rewound to operation {} of {}",
+                    last_run.0.position(),
+                    last_run.0.operations()
+                ));
+            }
+            if selected.0 == Schrödinger && ui.button("Stop Observing").clicked() {
+                observed.0 = false;
+            }
             ui.horizontal(|ui| {
                 if paused.0 {
                     if ui.button("Continue algorithm").clicked() {
@@ -233,16 +397,237 @@ fn settings_widget(
                 if ui.button("Stop algorithm").clicked() {
                     sorting.0 = false;
                     paused.0 = false;
-                    match selected.0 {
-                        Algorithm::Insertion => insertion_step.0 = (0, 0),
-                        Algorithm::Selection => selection_step.0 = (0, 0, 0),
-                        Algorithm::Merge => merge_step.0 = (1, 0, 0, 0, vec![], vec![]),
-                        _ => {}
-                    }
                 }
             });
+            //going back pauses the algorithm, continuing replays the recording up to where it was
+            if !last_run.0.truncated() {
+                ui.horizontal(|ui| {
+                    if ui.button("Step back 1 operation").clicked() {
+                        last_run.0.undo(&mut v.0);
+                        paused.0 = true;
+                    }
+                    if ui.button("Step back 1 frame").clicked() {
+                        for _ in 0..operations.0.max(1) {
+                            last_run.0.undo(&mut v.0);
+                        }
+                        paused.0 = true;
+                    }
+                });
+                let mut position = last_run.0.position();
+                let timeline =
+                    egui::Slider::new(&mut position, 0..=last_run.0.operations()).text("Timeline");
+                if ui.add(timeline).changed() {
+                    last_run.0.seek(&mut v.0, position);
+                    paused.0 = true;
+                }
+            }
+        }
+    });
+}
+
+//stepper whose state belongs to the list, the rewound one while the list is behind the recording
+fn shown<'a>(
+    active: &'a ActiveSort,
+    rewound: &'a Rewound,
+    last_run: &LastRun,
+) -> Option<&'a dyn SortStepper> {
+    if last_run.0.rewound() {
+        rewound.0.stepper()
+    } else {
+        Some(active.0.as_ref())
+    }
+}
+
+//runs the algorithm again up to the rewound position, so the list shows what it was doing there
+fn rewind_stepper(
+    mut rewound: ResMut<Rewound>,
+    last_run: Res<LastRun>,
+    sorting: Res<SortingOngoing>,
+    v: Res<List>,
+) {
+    if sorting.0 && last_run.0.rewound() {
+        rewound.0.seek(&last_run.0, &v.0, last_run.1, &last_run.2);
+    } else {
+        rewound.0.clear();
+    }
+}
+
+fn counters_ui(ui: &mut egui::Ui, counters: Counters) {
+    egui::Grid::new("counters").show(ui, |ui| {
+        ui.label(format!("Comparisons: {}", counters.comparisons));
+        ui.label(format!("Swaps: {}", counters.swaps));
+        ui.label(format!("Writes: {}", counters.writes));
+        ui.end_row();
+        ui.label(format!("Auxiliary writes: {}", counters.aux_writes));
+        ui.label(format!("Reads: {}", counters.reads));
+        ui.end_row();
+    });
+}
+
+//outcome of validation, every problem found gets its own red line
+fn verdict_ui(ui: &mut egui::Ui, verdict: &Verdict) {
+    let red = egui::Color32::RED;
+    if verdict.passed() {
+        ui.colored_label(
+            egui::Color32::GREEN,
+            "Validation passed: sorted and nothing lost",
+        );
+    }
+    if !verdict.unsorted.is_empty() {
+        let text = format!(
+            "Validation failed: {} pairs out of order",
+            verdict.unsorted.len()
+        );
+        ui.colored_label(red, text);
+    }
+    if !verdict.permutation {
+        ui.colored_label(red, "Validation failed: not a permutation of the input");
+    }
+    match verdict.unstable.as_deref() {
+        Some([]) => {
+            ui.label("Stable: equal keys kept their order");
+        }
+        Some(pairs) => {
+            let text = format!("Unstable: {} pairs of equal keys swapped", pairs.len());
+            ui.colored_label(red, text);
+        }
+        None => {}
+    }
+}
+
+//list of keys, packed into records while the list holds records
+fn keyed(keys: Vec<i32>, records: bool) -> Result<Vec<i32>, String> {
+    if !records {
+        return Ok(keys);
+    }
+    pack_records(&keys).ok_or_else(|| format!("Keys of records have to be within ±{}", MAX_KEY))
+}
+
+//typed or loaded list of numbers, returns it once it has been imported without errors
+fn import_ui(
+    ui: &mut egui::Ui,
+    import: &mut Import,
+    clipboard: &EguiClipboard,
+    records: bool,
+) -> Option<Vec<i32>> {
+    ui.label("Whole numbers separated by commas or new lines");
+    ui.add(egui::TextEdit::multiline(&mut import.0).desired_rows(3));
+    let mut text = None;
+    ui.horizontal(|ui| {
+        if ui.button("Import").clicked() {
+            text = Some(import.0.clone());
+        }
+        if ui.button("Paste from clipboard").clicked() {
+            text = clipboard.get_contents();
         }
     });
+    ui.horizontal(|ui| {
+        ui.label("File");
+        ui.text_edit_singleline(&mut import.1);
+        if ui.button("Load").clicked() {
+            match fs::read_to_string(&import.1) {
+                Ok(contents) => text = Some(contents),
+                Err(e) => import.2 = Some(Err(format!("Could not read {}: {}", import.1, e))),
+            }
+        }
+    });
+    let mut imported = None;
+    if let Some(text) = text {
+        let parsed = parse_list(&text).and_then(|keys| keyed(keys, records));
+        import.2 = Some(parsed.as_ref().map(Vec::len).map_err(String::clone));
+        imported = parsed.ok();
+        import.0 = text;
+    }
+    match &import.2 {
+        Some(Ok(len)) => {
+            ui.label(format!("Imported {} items", len));
+        }
+        Some(Err(e)) => {
+            ui.colored_label(egui::Color32::RED, e);
+        }
+        None => {}
+    }
+    imported
+}
+
+//resets the list to the start of the last run and replays it
+fn replay(
+    last_run: &mut LastRun,
+    v: &mut List,
+    n: &mut NumberOfItems,
+    selected: &mut SelectedAlgorithm,
+    active: &mut ActiveSort,
+) {
+    //the replay records the same trace again, so it can be replayed once more
+    let trace = std::mem::take(&mut last_run.0);
+    v.0 = trace.initial.clone();
+    n.0 = v.0.len() as i32;
+    selected.0 = last_run.1;
+    last_run.0 = Trace::new(trace.initial.clone());
+    last_run.0.records = trace.records;
+    last_run.3 = None;
+    active.0 = Box::new(Replay::new(trace));
+}
+
+//saves the last run to a json or csv file and loads runs back for replaying
+fn trace_widget(
+    mut contexts: EguiContexts,
+    mut path: Local<Option<String>>,
+    mut message: Local<String>,
+    mut last_run: ResMut<LastRun>,
+    mut v: ResMut<List>,
+    mut n: ResMut<NumberOfItems>,
+    mut selected: ResMut<SelectedAlgorithm>,
+    mut active: ResMut<ActiveSort>,
+    mut settings: ResMut<SortSettings>,
+    mut sorting: ResMut<SortingOngoing>,
+    mut paused: ResMut<Paused>,
+) {
+    let path = path.get_or_insert_with(|| "trace.json".to_string());
+    egui::Window::new("Trace file")
+        .default_open(false)
+        .show(contexts.ctx_mut(), |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Path");
+                ui.text_edit_singleline(path);
+            });
+            ui.label("Files ending in .csv are saved as csv, anything else as json");
+            if sorting.0 {
+                ui.label("Stop the algorithm to save or load a trace");
+                return;
+            }
+            ui.horizontal(|ui| {
+                let recorded = last_run.0.operations() > 0 && !last_run.0.truncated();
+                if ui
+                    .add_enabled(recorded, egui::Button::new("Export last run"))
+                    .clicked()
+                {
+                    let run = SavedRun::new(last_run.1, last_run.2, &last_run.0);
+                    *message = match run.save(Path::new(path)) {
+                        Ok(()) => format!("Saved {} operations", run.operations.len()),
+                        Err(e) => e,
+                    };
+                }
+                if ui.button("Import and replay").clicked() {
+                    match SavedRun::load(Path::new(path)).and_then(|run| run.check().map(|_| run)) {
+                        Ok(run) => {
+                            *message = format!("Loaded {} operations", run.operations.len());
+                            *settings = run.settings;
+                            *last_run =
+                                LastRun(Trace::default(), run.algorithm, run.settings, None);
+                            last_run.0 = run.trace();
+                            replay(&mut last_run, &mut v, &mut n, &mut selected, &mut active);
+                            sorting.0 = true;
+                            paused.0 = false;
+                        }
+                        Err(e) => *message = e,
+                    }
+                }
+            });
+            if !message.is_empty() {
+                ui.label(message.as_str());
+            }
+        });
 }
 
 fn speed_controller(
@@ -265,246 +650,337 @@ fn speed_controller(
     }
 }
 
-fn insertion_sort(
-    mut sort: ResMut<Sort>,
-    mut step: ResMut<InsertionStep>,
-    mut v: ResMut<List>,
-    mut selected: ResMut<SelectedAlgorithm>,
+fn sort_sound(
+    sort: Res<Sort>,
+    active: Res<ActiveSort>,
+    v: Res<List>,
+    selected: Res<SelectedAlgorithm>,
     operations: Res<Operations>,
-    mut end_step: ResMut<AnimationStep>,
+    observed: Res<Observed>,
     mut pitch_assets: ResMut<Assets<Pitch>>,
     mut frequency: ResMut<PitchFrequency>,
     mut commands: Commands,
-    sound: Res<Sound>, //comment out to run insertion test
+    sound: Res<Sound>,
+    last_run: Res<LastRun>,
+    rewound: Res<Rewound>,
 ) {
-    if sort.0 && selected.0 == Algorithm::Insertion {
-        if operations.0 != 0 && sound.0 {
-            //comment out to run insertion test
-            frequency.0 = 200. + 1500.0 * step.0 .1.pow(3) as f32 / v.0.len().pow(3) as f32; //comment out to run insertion test
-            commands.spawn(PitchBundle {
-                //comment out to run insertion test
-                source: pitch_assets.add(Pitch::new(frequency.0, Duration::from_millis(50))), //comment out to run insertion test
-                settings: PlaybackSettings::DESPAWN, //comment out to run insertion test
-            }); //comment out to run insertion test
-        } //comment out to run insertion test
-        for _ in 0..operations.0 {
-            let v = &mut v.0;
-            if step.0 .0 >= v.len() {
-                sort.0 = false;
-                step.0 = (0, 0);
-                end_step.0 .2 = Insertion;
-                selected.0 = Validation;
-                break;
-            } else {
-                if step.0 .1 > 0 && v[step.0 .1 - 1] > v[step.0 .1] {
-                    v.swap(step.0 .1 - 1, step.0 .1);
-                    step.0 .1 -= 1;
-                } else {
-                    step.0 .0 += 1;
-                    step.0 .1 = step.0 .0;
-                }
-            }
-        }
-        sort.0 = false;
+    if sort.0 && selected.0 != Validation && observed.0 && operations.0 != 0 && sound.0 {
+        let cursor = match shown(&active, &rewound, &last_run) {
+            Some(stepper) => stepper.cursor(),
+            None => last_run.0.cursor(),
+        };
+        //some cursors are values rather than indices, so they are kept to the top of the range
+        let height = (cursor as f32 / v.0.len() as f32).min(1.);
+        frequency.0 = 200. + 1500.0 * height.powi(3);
+        commands.spawn(PitchBundle {
+            source: pitch_assets.add(Pitch::new(frequency.0, Duration::from_millis(50))),
+            settings: PlaybackSettings::DESPAWN,
+        });
     }
 }
 
-fn selection_sort(
-    mut sort: ResMut<Sort>,
-    mut step: ResMut<SelectionStep>,
-    mut v: ResMut<List>,
-    mut selected: ResMut<SelectedAlgorithm>,
-    operations: Res<Operations>,
-    mut pitch_assets: ResMut<Assets<Pitch>>,
-    mut end_step: ResMut<AnimationStep>,
-    mut frequency: ResMut<PitchFrequency>,
-    mut commands: Commands,
-    sound: Res<Sound>, //comment out to run selection test
+//runs the picked algorithms headlessly over a range of list sizes and plots their costs against n
+fn cost_widget(
+    mut contexts: EguiContexts,
+    mut cost: ResMut<Cost>,
+    n: Res<NumberOfItems>,
+    settings: Res<SortSettings>,
 ) {
-    if sort.0 && selected.0 == Algorithm::Selection {
-        //comment out to run selection test
-        if operations.0 != 0 && sound.0 {
-            //comment out to run selection test
-            frequency.0 = 200. + 1500.0 * step.0 .1.pow(3) as f32 / v.0.len().pow(3) as f32; //comment out to run selection test
-            commands.spawn(PitchBundle {
-                //comment out to run selection test
-                source: pitch_assets.add(Pitch::new(frequency.0, Duration::from_millis(50))), //comment out to run selection test
-                settings: PlaybackSettings::DESPAWN, //comment out to run selection test
-            }); //comment out to run selection test
-        } //comment out to run selection test
-        for _ in 0..operations.0 {
-            let v = &mut v.0;
-            if step.0 .0 >= v.len() {
-                sort.0 = false;
-                step.0 = (0, 0, 0);
-                end_step.0 .2 = Selection;
-                selected.0 = Validation;
-                break;
-            } else {
-                if step.0 .1 == step.0 .0 + 1 {
-                    step.0 .2 = step.0 .0;
-                }
-                if step.0 .1 < v.len() {
-                    if v[step.0 .1] < v[step.0 .2] {
-                        step.0 .2 = step.0 .1;
-                    }
-                    step.0 .1 += 1;
-                } else {
-                    if step.0 .0 != step.0 .2 {
-                        v.swap(step.0 .0, step.0 .2);
+    let Cost(chart, metric, picked) = cost.as_mut();
+    //measuring is spread over frames so the chart fills in without freezing the window
+    let start = Instant::now();
+    while start.elapsed() < Duration::from_millis(20) && chart.step() {}
+
+    egui::Window::new("Cost chart")
+        .default_open(false)
+        .show(contexts.ctx_mut(), |ui| {
+            ui.collapsing("Algorithms", |ui| {
+                egui::Grid::new("cost algorithms").show(ui, |ui| {
+                    //schrödinger is left out as it may never finish
+                    let algorithms = Algorithm::ALL.into_iter().filter(|a| *a != Schrödinger);
+                    for (i, algorithm) in algorithms.enumerate() {
+                        let mut checked = picked.contains(&algorithm);
+                        if ui
+                            .checkbox(&mut checked, format!("{:?}", algorithm))
+                            .changed()
+                        {
+                            if checked {
+                                picked.push(algorithm);
+                            } else {
+                                picked.retain(|a| *a != algorithm);
+                            }
+                        }
+                        if i % 4 == 3 {
+                            ui.end_row();
+                        }
                     }
-                    step.0 .0 += 1;
-                    step.0 .1 = step.0 .0 + 1;
+                });
+            });
+            ui.horizontal(|ui| {
+                for m in Metric::ALL {
+                    ui.radio_value(metric, m, format!("{:?}", m));
                 }
-            }
-        }
-        sort.0 = false;
+            });
+            ui.horizontal(|ui| {
+                let button = egui::Button::new(format!("Measure up to n = {}", n.0));
+                if ui.add_enabled(!picked.is_empty(), button).clicked() {
+                    chart.start(picked, *settings, n.0 as usize);
+                }
+                if chart.measuring() {
+                    ui.add(egui::ProgressBar::new(chart.progress()).show_percentage());
+                }
+            });
+            cost_plot(ui, chart, *metric);
+        });
+}
+
+//measured curves in the palette colors, reference curves dashed in gray
+fn cost_plot(ui: &mut egui::Ui, chart: &CostChart, metric: Metric) {
+    let points = chart.curves.iter().flat_map(|(_, points)| points);
+    let max_n = points.clone().map(|(n, _)| *n).max().unwrap_or(1).max(1) as f32;
+    let max_cost = points.map(|(_, c)| metric.of(c)).max().unwrap_or(1).max(1) as f32;
+
+    let (rect, _) = ui.allocate_exact_size(egui::vec2(420., 260.), egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    let to_screen = |n: f32, cost: f32| {
+        egui::pos2(
+            rect.left() + rect.width() * n / max_n,
+            rect.bottom() - rect.height() * cost / max_cost,
+        )
+    };
+    painter.rect_stroke(rect, 0., ui.visuals().widgets.noninteractive.bg_stroke);
+
+    let gray = egui::Stroke::new(1., egui::Color32::GRAY);
+    for name in ["n", "n log n", "n²"] {
+        let f = |n: f32| match name {
+            "n" => n,
+            "n log n" => n * n.max(1.).log2(),
+            _ => n * n,
+        };
+        let line: Vec<egui::Pos2> = (0..=100)
+            .map(|i| max_n * i as f32 / 100.)
+            .map(|n| to_screen(n, f(n)))
+            .collect();
+        painter.extend(egui::Shape::dashed_line(&line, gray, 4., 4.));
+        //the label goes where the curve leaves the plot
+        let end = line
+            .iter()
+            .copied()
+            .take_while(|p| p.y >= rect.top())
+            .last()
+            .unwrap_or(rect.left_bottom());
+        painter.text(
+            end,
+            egui::Align2::RIGHT_BOTTOM,
+            name,
+            egui::FontId::default(),
+            egui::Color32::GRAY,
+        );
     }
+
+    for (i, (_, points)) in chart.curves.iter().enumerate() {
+        let [r, g, b, _] = palette(i).as_rgba_u8();
+        let stroke = egui::Stroke::new(2., egui::Color32::from_rgb(r, g, b));
+        let line = points
+            .iter()
+            .map(|(n, c)| to_screen(*n as f32, metric.of(c) as f32));
+        painter.add(egui::Shape::line(line.collect(), stroke));
+    }
+
+    ui.label(format!(
+        "n up to {}, {:?} up to {}",
+        max_n, metric, max_cost
+    ));
+    ui.horizontal_wrapped(|ui| {
+        for (i, (algorithm, _)) in chart.curves.iter().enumerate() {
+            let [r, g, b, _] = palette(i).as_rgba_u8();
+            let color = egui::Color32::from_rgb(r, g, b);
+            ui.colored_label(color, format!("{:?}", algorithm));
+        }
+    });
 }
 
-fn merge_sort(
-    mut sort: ResMut<Sort>,
-    mut step: ResMut<MergeStep>,
+//sets up a race of 2-6 algorithms on copies of the list and shows its results
+fn race_widget(
+    mut contexts: EguiContexts,
+    mut picked: Local<Vec<Algorithm>>,
+    mut racing: ResMut<Racing>,
     mut v: ResMut<List>,
-    mut selected: ResMut<SelectedAlgorithm>,
-    operations: Res<Operations>,
-    mut end_step: ResMut<AnimationStep>,
-    mut pitch_assets: ResMut<Assets<Pitch>>,
-    mut frequency: ResMut<PitchFrequency>,
-    mut commands: Commands,
-    sound: Res<Sound>, //comment out to run merge test
+    settings: Res<SortSettings>,
+    sorting: Res<SortingOngoing>,
+    windows: Query<&Window>,
 ) {
-    if sort.0 && selected.0 == Algorithm::Merge {
-        //comment out to run merge test
-        let left = step.0 .0 * step.0 .1 * 2; //comment out to run merge test
-        let i1 = step.0 .2; //comment out to run merge test
-        let i2 = step.0 .3; //comment out to run merge test
-        let i = left + i1 + i2; //comment out to run merge test
-        if operations.0 != 0 && sound.0 {
-            //comment out to run merge test
-            frequency.0 = 200. + 1500.0 * i.pow(3) as f32 / v.0.len().pow(3) as f32; //comment out to run merge test
-            commands.spawn(PitchBundle {
-                //comment out to run merge test
-                source: pitch_assets.add(Pitch::new(frequency.0, Duration::from_millis(50))), //comment out to run merge test
-                settings: PlaybackSettings::DESPAWN, //comment out to run merge test
-            }); //comment out to run merge test
-        } //comment out to run merge test
-        let mut operation = 0;
-        while operation < operations.0 {
-            operation += 1;
-            let v = &mut v.0;
-            if step.0 .0 >= v.len() {
-                sort.0 = false;
-                step.0 = (1, 0, 0, 0, vec![], vec![]);
-                end_step.0 .2 = Merge;
-                selected.0 = Validation;
-                break;
-            } else {
-                if step.0 .0 == 1 && step.0 .1 == 0 && step.0 .2 == 0 && step.0 .3 == 0 {
-                    step.0 .4 = v[..step.0 .0].to_vec();
-                    step.0 .5 = v[step.0 .0..step.0 .0 * 2].to_vec();
+    //schrödinger is left out as it may never finish
+    let algorithms: Vec<Algorithm> = Algorithm::ALL
+        .into_iter()
+        .filter(|a| *a != Schrödinger)
+        .collect();
+    if picked.is_empty() {
+        *picked = vec![Insertion, Merge];
+    }
+    //a sort started from the controller takes the window back
+    if sorting.0 {
+        racing.0 = None;
+    }
+    let ctx = contexts.ctx_mut();
+    egui::Window::new("Race")
+        .default_open(false)
+        .show(ctx, |ui| {
+            if racing.0.is_none() {
+                let mut panes = picked.len();
+                ui.add(egui::Slider::new(&mut panes, 2..=6).text("Panes"));
+                while picked.len() < panes {
+                    let next = algorithms[(picked.len() * 5) % algorithms.len()];
+                    picked.push(next);
+                }
+                picked.truncate(panes);
+                for (i, pick) in picked.iter_mut().enumerate() {
+                    egui::ComboBox::from_label(format!("Pane {}", i + 1))
+                        .selected_text(format!("{:?}", pick))
+                        .show_ui(ui, |ui| {
+                            for &algorithm in &algorithms {
+                                ui.selectable_value(pick, algorithm, format!("{:?}", algorithm));
+                            }
+                        });
+                }
+                let refused = picked
+                    .iter()
+                    .find_map(|a| a.check(&v.0, settings.records).err());
+                if let Some(e) = &refused {
+                    ui.colored_label(egui::Color32::RED, e);
                 }
-                let left = step.0 .0 * step.0 .1 * 2;
-                let i1 = step.0 .2;
-                let v1 = &step.0 .4;
-                let i2 = step.0 .3;
-                let v2 = &step.0 .5;
-                let i = left + i1 + i2;
-                if i >= v.len() {
-                    step.0 .0 *= 2;
-                    if step.0 .0 >= v.len() {
-                        continue;
+                if sorting.0 {
+                    ui.label("Stop the algorithm to start a race");
+                } else if ui
+                    .add_enabled(refused.is_none(), egui::Button::new("Start race"))
+                    .clicked()
+                {
+                    if settings.records {
+                        v.0 = repack_records(&v.0);
                     }
-                    (step.0 .1, step.0 .2, step.0 .3) = (0, 0, 0);
-                    step.0 .4 = v[..step.0 .0].to_vec();
-                    step.0 .5 = v[step.0 .0..min(step.0 .0 * 2, v.len())].to_vec();
-                    continue;
+                    racing.0 = Some(Race::new(&picked, &settings, &v.0));
+                    racing.1 = false;
                 }
-                if i1 < v1.len() {
-                    if i2 < v2.len() {
-                        if v1[i1] < v2[i2] {
-                            v[i] = v1[i1];
-                            step.0 .2 += 1;
-                        } else {
-                            v[i] = v2[i2];
-                            step.0 .3 += 1;
-                        }
+                return;
+            }
+            let finished = racing.0.as_ref().is_some_and(Race::finished);
+            ui.horizontal(|ui| {
+                if !finished {
+                    let label = if racing.1 {
+                        "Continue race"
                     } else {
-                        v[i] = v1[i1];
-                        step.0 .2 += 1;
+                        "Pause race"
+                    };
+                    if ui.button(label).clicked() {
+                        racing.1 = !racing.1;
                     }
-                } else if i2 < v2.len() {
-                    if i1 < v1.len() {
-                        if v1[i1] < v2[i2] {
-                            v[i] = v1[i1];
-                            step.0 .2 += 1;
-                        } else {
-                            v[i] = v2[i2];
-                            step.0 .3 += 1;
-                        }
-                    } else {
-                        v[i] = v2[i2];
-                        step.0 .3 += 1;
+                }
+                if ui.button("Close race").clicked() {
+                    racing.0 = None;
+                }
+            });
+            let Some(race) = &racing.0 else {
+                return;
+            };
+            egui::Grid::new("race results")
+                .striped(true)
+                .show(ui, |ui| {
+                    for heading in ["Pane", "Algorithm", "Place", "Operations"] {
+                        ui.label(heading);
                     }
-                } else {
-                    operation -= 1;
-                    step.0 .1 += 1;
-                    (step.0 .2, step.0 .3) = (0, 0);
-                    step.0 .4 = v[step.0 .0 * 2 * step.0 .1
-                        ..min(step.0 .0 * 2 * step.0 .1 + step.0 .0, v.len())]
-                        .to_vec();
-                    if step.0 .0 * 2 * step.0 .1 + step.0 .0 < v.len() {
-                        step.0 .5 = v[step.0 .0 * 2 * step.0 .1 + step.0 .0
-                            ..min(step.0 .0 * 2 * step.0 .1 + step.0 .0 * 2, v.len())]
-                            .to_vec();
-                    } else {
-                        step.0 .5 = vec![];
+                    for heading in ["Comparisons", "Swaps", "Writes", "Aux writes", "Reads"] {
+                        ui.label(heading);
                     }
-                }
-            }
-        }
-        sort.0 = false;
+                    ui.end_row();
+                    //finished lanes first in their finish order, then the ones still sorting
+                    let running = (0..race.lanes.len()).filter(|i| !race.finish.contains(i));
+                    for i in race.finish.iter().copied().chain(running) {
+                        let lane = &race.lanes[i];
+                        let c = lane.counters;
+                        ui.label((i + 1).to_string());
+                        ui.label(format!("{:?}", lane.algorithm));
+                        match race.place(i) {
+                            Some(place) => ui.label(place.to_string()),
+                            None => ui.label("running"),
+                        };
+                        for count in [lane.operations, c.comparisons, c.swaps, c.writes] {
+                            ui.label(count.to_string());
+                        }
+                        ui.label(c.aux_writes.to_string());
+                        ui.label(c.reads.to_string());
+                        ui.end_row();
+                    }
+                });
+        });
+
+    //names of the algorithms at the top of their panes
+    let Some(race) = &racing.0 else {
+        return;
+    };
+    let window = windows.single();
+    let (width, height) = (window.width(), window.height());
+    for (i, lane) in race.lanes.iter().enumerate() {
+        let (corner, size) = pane(i, race.lanes.len(), width, height);
+        let top_left = egui::pos2(corner.x + width / 2., height / 2. - corner.y - size.y);
+        egui::Area::new(egui::Id::new(("race pane", i)))
+            .fixed_pos(top_left)
+            .interactable(false)
+            .show(ctx, |ui| {
+                let text = match race.place(i) {
+                    Some(place) => format!("{}. {:?} (finished {})", i + 1, lane.algorithm, place),
+                    None => format!("{}. {:?}", i + 1, lane.algorithm),
+                };
+                ui.label(text);
+            });
     }
 }
 
-fn schrödinger_sort(
+//advances every pane of the race by the same operations per frame as a single sort
+fn run_race(mut racing: ResMut<Racing>, operations: Res<Operations>, max_speed: Res<MaxSpeed>) {
+    let Racing(Some(race), false) = racing.as_mut() else {
+        return;
+    };
+    if max_speed.0 == Speed::Max {
+        race.step(100000);
+    } else {
+        race.step(operations.0);
+    }
+}
+
+//bottom left corner and size of pane i out of panes, in world coordinates
+//up to three panes share a row, more are split over two rows
+fn pane(i: usize, panes: usize, window_width: f32, window_height: f32) -> (Vec2, Vec2) {
+    let rows = if panes > 3 { 2 } else { 1 };
+    let columns = panes.div_ceil(rows);
+    let size = Vec2::new(
+        window_width / columns as f32,
+        (window_height - 200.) / rows as f32,
+    );
+    let (row, column) = (i / columns, i % columns);
+    let corner = Vec2::new(
+        -window_width / 2. + column as f32 * size.x,
+        -window_height / 2. + (rows - 1 - row) as f32 * size.y,
+    );
+    (corner, size)
+}
+
+fn run_sort(
     mut sort: ResMut<Sort>,
-    observed: Res<Observed>,
+    mut active: ResMut<ActiveSort>,
     mut v: ResMut<List>,
     mut selected: ResMut<SelectedAlgorithm>,
     operations: Res<Operations>,
+    observed: Res<Observed>,
     mut end_step: ResMut<AnimationStep>,
-    mut pitch_assets: ResMut<Assets<Pitch>>,
-    mut frequency: ResMut<PitchFrequency>,
-    mut commands: Commands,
-    sound: Res<Sound>,
+    mut last_run: ResMut<LastRun>,
 ) {
-    if sort.0 && selected.0 == Algorithm::Schrödinger {
-        if observed.0 {
-            if operations.0 != 0 && sound.0 {
-                frequency.0 = 200. + 1500.0 * v.0[0].pow(3) as f32 / v.0.len().pow(3) as f32;
-                commands.spawn(PitchBundle {
-                    source: pitch_assets.add(Pitch::new(frequency.0, Duration::from_millis(50))),
-                    settings: PlaybackSettings::DESPAWN,
-                });
-            }
-        }
+    if sort.0 && selected.0 != Validation {
         for _ in 0..operations.0 {
-            let mut sorted = true;
-            for i in 1..v.0.len() {
-                if v.0[i - 1] > v.0[i] {
-                    sorted = false;
-                }
-            }
-            if sorted {
-                if observed.0 {
-                    sort.0 = false;
-                    end_step.0 .2 = Schrödinger;
-                    selected.0 = Validation;
-                    break;
-                }
-            } else {
-                v.0.shuffle(&mut rand::thread_rng());
+            //a sorted list only counts as sorted once it is observed
+            if last_run.0.step(active.0.as_mut(), &mut v.0) && observed.0 {
+                end_step.0 .2 = selected.0;
+                selected.0 = Validation;
+                break;
             }
         }
         sort.0 = false;
@@ -522,6 +998,7 @@ fn end_animation(
     mut frequency: ResMut<PitchFrequency>,
     mut commands: Commands,
     sound: Res<Sound>,
+    mut last_run: ResMut<LastRun>,
 ) {
     if sort.0 && selected.0 == Algorithm::Validation {
         if sound.0 {
@@ -533,13 +1010,16 @@ fn end_animation(
         }
         for _ in 0..operations.0 {
             if step.0 .0 == 0 {
+                //the list is checked up front, the sweep then shows what was found
+                let LastRun(trace, _, settings, verdict) = last_run.as_mut();
+                *verdict = Some(Verdict::of(&trace.initial, &v.0, settings.records));
                 step.0 .1 = operations.0;
                 operations.0 = 1 + (v.0.len() / 100) as u32;
                 step.0 .0 += 1;
                 break;
             } else if step.0 .0 >= v.0.len() - 1 {
                 step.0 .0 = 0;
-                operations.0 = step.0 .1 as u32;
+                operations.0 = step.0 .1;
                 selected.0 = step.0 .2;
                 sorting.0 = false;
                 sort.0 = false;
@@ -552,6 +1032,13 @@ fn end_animation(
     }
 }
 
+//the seed is kept in the window title so it shows on every screenshot
+fn seed_title(settings: Res<SortSettings>, mut windows: Query<&mut Window>) {
+    if settings.is_changed() {
+        windows.single_mut().title = format!("Seed {}", settings.seed);
+    }
+}
+
 fn setup(mut commands: Commands) {
     commands.spawn(Camera2dBundle::default());
     commands.insert_resource(PitchFrequency(1000.0));
@@ -563,12 +1050,14 @@ fn render_list(
     sprites: Query<Entity, With<Sprite>>,
     windows: Query<&Window>,
     selected: Res<SelectedAlgorithm>,
-    insertion_step: Res<InsertionStep>,
-    selection_step: Res<SelectionStep>,
-    merge_step: Res<MergeStep>,
+    active: Res<ActiveSort>,
     end_step: Res<AnimationStep>,
     ongoing: Res<SortingOngoing>,
     observed: Res<Observed>,
+    last_run: Res<LastRun>,
+    racing: Res<Racing>,
+    settings: Res<SortSettings>,
+    rewound: Res<Rewound>,
 ) {
     sprites.for_each(|entity| {
         commands.entity(entity).despawn();
@@ -576,185 +1065,361 @@ fn render_list(
     let window = windows.single();
     let (window_width, window_height) = (window.width(), window.height());
 
+    if let Some(race) = &racing.0 {
+        render_race(&mut commands, race, window_width, window_height);
+        return;
+    }
+
     let len = v.0.len() as f32;
 
-    for (n, i) in v.0.iter().zip(0..) {
-        let mut color = Color::WHITE;
-        if ongoing.0 {
-            match selected.0 {
-                Algorithm::Insertion => {
-                    if i as usize == insertion_step.0 .1 {
-                        color = Color::RED;
-                    } else if i as usize <= insertion_step.0 .0 {
-                        color = Color::GREEN;
-                    }
-                }
-                Algorithm::Selection => {
-                    if (i as usize) < selection_step.0 .0 {
-                        color = Color::GREEN;
-                    } else if i as usize == selection_step.0 .1 {
-                        color = Color::RED;
-                    } else if i as usize == selection_step.0 .2 {
-                        color = Color::BLUE;
-                    }
-                }
-                Algorithm::Merge => {
-                    let left = merge_step.0 .0 * merge_step.0 .1 * 2;
-                    if i >= left && i < left + merge_step.0 .2 + merge_step.0 .3 {
-                        color = Color::GREEN;
-                    }
-                }
-                Algorithm::Schrödinger => {
-                    if !observed.0 {
-                        break;
-                    }
-                }
-                Algorithm::Validation => {
-                    if i <= end_step.0 .0 {
-                        color = Color::GREEN;
-                    }
-                }
-            }
-        }
+    //buckets and counts of the running algorithm are drawn as a strip under the list
+    //while the list is rewound they come from the algorithm run again up to there
+    let running = ongoing.0 && observed.0 && selected.0 != Validation;
+    let shown = shown(&active, &rewound, &last_run).filter(|_| running);
+    let (buckets, histogram) = match shown {
+        Some(stepper) => (stepper.buckets(), stepper.histogram()),
+        None => (vec![], vec![]),
+    };
+    let strip_height = if buckets.is_empty() && histogram.is_empty() {
+        0.
+    } else {
+        window_height / 4.
+    };
+    //records are drawn by their keys and colored by where they started
+    let records = settings.records;
+    let key = |x: i32| if records { record_key(x) } else { x };
+    let keys: Vec<i32> = v.0.iter().map(|&x| key(x)).collect();
+    let scale = Scale::of(&keys);
+    //spawns bar i out of slots bars spread over the window width, a negative height hangs below bottom
+    let mut bar = |i: usize, slots: f32, height: f32, bottom: f32, color: Color| {
         commands.spawn(SpriteBundle {
             sprite: Sprite {
-                color: color,
-                custom_size: Some(Vec2::new(
-                    0.9 * window_width / len,
-                    (window_height - 200.) * *n as f32 / len,
-                )),
+                color,
+                custom_size: Some(Vec2::new(0.9 * window_width / slots, height.abs())),
                 anchor: Anchor::BottomLeft,
                 ..default()
             },
             transform: Transform::from_translation(Vec3::new(
-                -window_width / 2. + i as f32 * window_width / len + 0.05 * window_width / len,
-                -window_height / 2.,
+                -window_width / 2. + i as f32 * window_width / slots + 0.05 * window_width / slots,
+                bottom + height.min(0.),
                 0.,
             )),
             ..default()
         });
+    };
+
+    //buckets alternate colors so their borders are visible
+    let elements = buckets
+        .iter()
+        .enumerate()
+        .flat_map(|(b, bucket)| bucket.iter().map(move |n| (b, n)));
+    for ((b, n), i) in elements.zip(0..) {
+        let color = if b % 2 == 0 {
+            Color::CYAN
+        } else {
+            Color::VIOLET
+        };
+        let (zero, height) = scale.bar(key(*n), strip_height - 10.);
+        bar(i, len, height, -window_height / 2. + zero, color);
+    }
+
+    let max_count = histogram.iter().max().copied().unwrap_or(1).max(1) as f32;
+    for (count, i) in histogram.iter().zip(0..) {
+        let height = (strip_height - 10.) * *count as f32 / max_count;
+        bar(
+            i,
+            histogram.len() as f32,
+            height,
+            -window_height / 2.,
+            Color::CYAN,
+        );
     }
-}
-//IMPORTANT
-//to run these tests, it is unfortunately a requirement to comment out all the sound from all the algorithms as bevy wont create an event loop outside of the main thread
-//all the lines that need to be commented out are marked by comments
-#[test]
-fn insertion_sort_test() {
-    //checking if insertion sort is correct for one random vec for each len 1-1000
-    use rand::{thread_rng, Rng};
-    let mut app = App::new();
-    app.add_systems(Update, insertion_sort);
-    app.insert_resource(SelectedAlgorithm(Insertion));
-    app.insert_resource(Operations(u32::MAX));
-    app.insert_resource(InsertionStep((0, 0)));
-    app.insert_resource(Sort(true));
-    app.insert_resource(List((0..=100).collect()));
-    app.insert_resource(AnimationStep((0, 0, Insertion)));
 
-    for len in 1..=1000 {
-        let mut step = app.world.resource_mut::<InsertionStep>();
-        step.0 = (0, 0);
-        let mut sort = app.world.resource_mut::<Sort>();
-        sort.0 = true;
-        let mut selected = app.world.resource_mut::<SelectedAlgorithm>();
-        selected.0 = Insertion;
-        let mut v = app.world.resource_mut::<List>();
-        let random_vec: Vec<i32> = (0..len).map(|_| thread_rng().gen::<i32>()).collect();
-        v.0 = random_vec;
-
-        app.update();
-
-        let v = app.world.resource::<List>();
-        let mut sorted = true;
-        for i in 1..v.0.len() {
-            if v.0[i - 1] > v.0[i] {
-                sorted = false;
+    //pairs out of order and equal keys out of their original order turn red as validation passes them
+    let verdict = last_run.3.as_ref().filter(|_| selected.0 == Validation);
+    for (n, i) in v.0.iter().zip(0..) {
+        let mut color = if records {
+            record_color(*n, v.0.len())
+        } else {
+            Color::WHITE
+        };
+        if ongoing.0 {
+            if !observed.0 {
+                break;
+            }
+            if selected.0 == Validation {
+                if i <= end_step.0 .0 {
+                    let flagged = verdict.is_some_and(|verdict| verdict.flagged(i));
+                    color = if flagged { Color::RED } else { Color::GREEN };
+                }
+            } else if let Some(stepper) = shown {
+                color = stepper.color(i).unwrap_or(color);
+            } else {
+                color = last_run.0.color(i).unwrap_or(color);
             }
         }
-        assert_eq!(sorted, true);
-        assert_eq!(app.world.resource::<Sort>().0, false);
+        let (zero, height) = scale.bar(key(*n), window_height - 200. - strip_height);
+        bar(
+            i,
+            len,
+            height,
+            -window_height / 2. + strip_height + zero,
+            color,
+        );
     }
 }
 
-#[test]
-fn selection_sort_test() {
-    //checking if selection sort is correct for one random vec for each len 1-1000
-    use rand::{thread_rng, Rng};
-    let mut app = App::new();
+//every lane of the race drawn in its own pane, finished lanes turn green
+fn render_race(commands: &mut Commands, race: &Race, window_width: f32, window_height: f32) {
+    for (p, lane) in race.lanes.iter().enumerate() {
+        let (corner, size) = pane(p, race.lanes.len(), window_width, window_height);
+        let slot = size.x / lane.v.len() as f32;
+        let keys = if race.records {
+            unpack_records(&lane.v)
+        } else {
+            lane.v.clone()
+        };
+        let scale = Scale::of(&keys);
+        for (i, (&n, &key)) in lane.v.iter().zip(&keys).enumerate() {
+            let (zero, height) = scale.bar(key, size.y - 30.);
+            let color = if race.records {
+                record_color(n, lane.v.len())
+            } else {
+                Color::WHITE
+            };
+            commands.spawn(SpriteBundle {
+                sprite: Sprite {
+                    color: lane.color(i).unwrap_or(color),
+                    custom_size: Some(Vec2::new(0.9 * slot, height.abs())),
+                    anchor: Anchor::BottomLeft,
+                    ..default()
+                },
+                transform: Transform::from_translation(Vec3::new(
+                    corner.x + i as f32 * slot + 0.05 * slot,
+                    corner.y + zero + height.min(0.),
+                    0.,
+                )),
+                ..default()
+            });
+        }
+    }
+}
 
-    app.add_systems(Update, selection_sort);
-    app.insert_resource(SelectedAlgorithm(Selection));
-    app.insert_resource(Operations(u32::MAX));
-    app.insert_resource(SelectionStep((0, 0, 0)));
-    app.insert_resource(Sort(true));
-    app.insert_resource(SortingOngoing(true));
-    app.insert_resource(List((0..=100).collect()));
+//maps values to bar heights over the range of the list, which always includes 0
+//so a list of 1..=n fills the height and negative values hang below a zero line
+#[derive(Clone, Copy)]
+struct Scale {
+    lo: f32,   //lowest value or 0
+    span: f32, //from lo to the highest value or 0
+}
+
+impl Scale {
+    fn of(v: &[i32]) -> Self {
+        let lo = v.iter().copied().min().unwrap_or(0).min(0) as f32;
+        let hi = v.iter().copied().max().unwrap_or(0).max(0) as f32;
+        Scale {
+            lo,
+            span: (hi - lo).max(1.),
+        }
+    }
+
+    //height of the zero line and signed height of the bar of x, in an area of the given height
+    fn bar(self, x: i32, height: f32) -> (f32, f32) {
+        (-self.lo / self.span * height, x as f32 / self.span * height)
+    }
+
+    //x moved up by dy in an area of the given height, it can leave the range of the list
+    fn shift(self, x: i32, dy: f32, height: f32) -> i32 {
+        let value = x as f64 + (dy / height * self.span) as f64;
+        value.round().clamp(i32::MIN as f64, i32::MAX as f64) as i32
+    }
+}
 
-    for len in 1..=1000 {
-        let mut step = app.world.resource_mut::<SelectionStep>();
-        step.0 = (0, 0, 0);
-        let mut sort = app.world.resource_mut::<Sort>();
-        sort.0 = true;
-        let mut selected = app.world.resource_mut::<SelectedAlgorithm>();
-        selected.0 = Selection;
-        let mut v = app.world.resource_mut::<List>();
-        let random_vec: Vec<i32> = (0..len).map(|_| thread_rng().gen::<i32>()).collect();
-        v.0 = random_vec;
-
-        app.update();
-
-        let v = app.world.resource::<List>();
-        let mut sorted = true;
-        for i in 1..v.0.len() {
-            if v.0[i - 1] > v.0[i] {
-                sorted = false;
+#[derive(Clone, Copy)]
+enum DragMode {
+    Value(Scale, i32), //dragging up and down shifts the value the bar had, on the scale the list had then
+    Move,              //dragging sideways moves the bar to another index
+}
+
+//bar being dragged, where the drag started and what it does once the mouse has moved enough to tell
+struct Drag(usize, Vec2, Option<DragMode>);
+
+//lets the bars be dragged with the mouse while no algorithm is running
+fn edit_list(
+    mut contexts: EguiContexts,
+    mut drag: Local<Option<Drag>>,
+    mut v: ResMut<List>,
+    buttons: Res<Input<MouseButton>>,
+    windows: Query<&Window>,
+    sorting: Res<SortingOngoing>,
+    racing: Res<Racing>,
+    settings: Res<SortSettings>,
+) {
+    if sorting.0 || racing.0.is_some() || v.0.is_empty() || !buttons.pressed(MouseButton::Left) {
+        *drag = None;
+        return;
+    }
+    let window = windows.single();
+    let Some(cursor) = window.cursor_position() else {
+        return;
+    };
+    let (width, height) = (window.width(), window.height());
+    let len = v.0.len();
+    let slot = ((cursor.x / width * len as f32) as usize).min(len - 1);
+
+    //a drag starts on a click that is not meant for one of the windows
+    if buttons.just_pressed(MouseButton::Left) && !contexts.ctx_mut().is_pointer_over_area() {
+        *drag = Some(Drag(slot, cursor, None));
+    }
+    let Some(Drag(index, start, mode)) = drag.as_mut() else {
+        return;
+    };
+    if mode.is_none() {
+        let moved = cursor - *start;
+        if moved.length() < 5. {
+            return;
+        }
+        //the scale stays the one of the start for the whole drag, so a bar can be dragged
+        //past the highest value or below zero while the list is rescaled under it
+        *mode = Some(if moved.x.abs() > moved.y.abs() {
+            DragMode::Move
+        } else {
+            let keys = if settings.records {
+                unpack_records(&v.0)
+            } else {
+                v.0.clone()
+            };
+            DragMode::Value(Scale::of(&keys), keys[*index])
+        });
+    }
+    match *mode {
+        Some(DragMode::Value(scale, key)) => {
+            //same height as render_list, the list takes the window height minus 200
+            //a record gets a new key and keeps where it started
+            let key = scale.shift(key, start.y - cursor.y, height - 200.);
+            if settings.records {
+                v.0[*index] = record(key, record_position(v.0[*index]));
+            } else {
+                v.0[*index] = key;
             }
         }
+        Some(DragMode::Move) if slot != *index => {
+            let x = v.0.remove(*index);
+            v.0.insert(slot, x);
+            *index = slot;
+        }
+        _ => {}
+    }
+}
 
-        assert_eq!(sorted, true);
-        assert_eq!(app.world.resource::<Sort>().0, false);
-        assert_eq!(app.world.resource::<SortingOngoing>().0, false);
+fn render_heap_tree(
+    mut gizmos: Gizmos,
+    v: Res<List>,
+    windows: Query<&Window>,
+    selected: Res<SelectedAlgorithm>,
+    active: Res<ActiveSort>,
+    ongoing: Res<SortingOngoing>,
+    observed: Res<Observed>,
+    heap_tree: Res<HeapTree>,
+    last_run: Res<LastRun>,
+    rewound: Res<Rewound>,
+) {
+    if !heap_tree.0 || !ongoing.0 || !observed.0 || selected.0 == Validation {
+        return;
+    }
+    let Some(stepper) = shown(&active, &rewound, &last_run) else {
+        return;
+    };
+    let Some(size) = stepper.heap_size() else {
+        return;
+    };
+    let window = windows.single();
+    let (window_width, window_height) = (window.width(), window.height());
+
+    //levels are spread over the upper half of the window, every level splits its width evenly
+    let levels = (usize::BITS - v.0.len().leading_zeros()).max(1) as f32;
+    let level_height = ((window_height / 2. - 60.) / levels).min(60.);
+    let radius = (window_width / 2_f32.powf(levels) / 3.).clamp(1., 8.);
+    let position = |node: usize| {
+        let level = usize::BITS - 1 - (node + 1).leading_zeros();
+        let index = node + 1 - (1 << level);
+        let width = window_width / (1 << level) as f32;
+        Vec2::new(
+            -window_width / 2. + (index as f32 + 0.5) * width,
+            window_height / 2. - 30. - level as f32 * level_height,
+        )
+    };
+
+    for node in 0..size.min(v.0.len()) {
+        let color = stepper.color(node).unwrap_or(Color::WHITE);
+        if node > 0 {
+            gizmos.line_2d(position((node - 1) / 2), position(node), Color::GRAY);
+        }
+        gizmos.circle_2d(position(node), radius, color);
     }
 }
 
 #[test]
-fn merge_sort_test() {
-    //checking if merge sort is correct for one random vec for each len 1-1000
+fn run_sort_test() {
+    //checking that the sorting system drives any stepper to a sorted list and hands over to validation
     use rand::{thread_rng, Rng};
     let mut app = App::new();
 
-    app.add_systems(Update, merge_sort);
-    app.insert_resource(SelectedAlgorithm(Merge));
+    app.add_systems(Update, run_sort);
+    app.insert_resource(SelectedAlgorithm(Insertion));
     app.insert_resource(Operations(u32::MAX));
-    app.insert_resource(MergeStep((1, 0, 0, 0, vec![], vec![])));
+    app.insert_resource(ActiveSort(Insertion.stepper(&SortSettings::default())));
     app.insert_resource(Sort(true));
-    app.insert_resource(SortingOngoing(true));
+    app.insert_resource(Observed(true));
     app.insert_resource(List((0..=100).collect()));
+    app.insert_resource(AnimationStep((0, 0, Insertion)));
+    app.insert_resource(LastRun(
+        Trace::default(),
+        Insertion,
+        SortSettings::default(),
+        None,
+    ));
 
-    for len in 1..=1000 {
-        let mut step = app.world.resource_mut::<MergeStep>();
-        step.0 = (1, 0, 0, 0, vec![], vec![]);
-        let mut sort = app.world.resource_mut::<Sort>();
-        sort.0 = true;
-        let mut selected = app.world.resource_mut::<SelectedAlgorithm>();
-        selected.0 = Merge;
-        let mut v = app.world.resource_mut::<List>();
-        let random_vec: Vec<i32> = (0..len).map(|_| thread_rng().gen::<i32>()).collect();
-        v.0 = random_vec;
-
-        app.update();
-
-        let v = app.world.resource::<List>();
-
-        let mut sorted = true;
-        for i in 1..v.0.len() {
-            if v.0[i - 1] > v.0[i] {
-                sorted = false;
-            }
-        }
+    //schrödinger is left out as it may never finish
+    for algorithm in Algorithm::ALL.into_iter().filter(|a| *a != Schrödinger) {
+        for len in 1..=100 {
+            app.world.resource_mut::<ActiveSort>().0 = algorithm.stepper(&SortSettings::default());
+            app.world.resource_mut::<Sort>().0 = true;
+            app.world.resource_mut::<SelectedAlgorithm>().0 = algorithm;
+            let random_vec: Vec<i32> = (0..len)
+                .map(|_| thread_rng().gen_range(-len..len))
+                .collect();
+            app.world.resource_mut::<List>().0 = random_vec;
 
-        assert_eq!(sorted, true);
-        assert_eq!(app.world.resource::<Sort>().0, false);
-        assert_eq!(app.world.resource::<SortingOngoing>().0, false);
+            app.update();
+
+            let v = app.world.resource::<List>();
+            assert!(v.0.windows(2).all(|w| w[0] <= w[1]));
+            assert!(!app.world.resource::<Sort>().0);
+            assert_eq!(app.world.resource::<SelectedAlgorithm>().0, Validation);
+            assert_eq!(app.world.resource::<AnimationStep>().0 .2, algorithm);
+        }
     }
 }
+
+#[test]
+fn scale_test() {
+    //checking that 1..=n fills the height from the bottom, that negative values hang below zero
+    //and that drags shift values by the scale
+    let scale = Scale::of(&(1..=100).collect::<Vec<_>>());
+    assert_eq!(scale.bar(100, 500.), (0., 500.));
+    assert_eq!(scale.bar(50, 500.), (0., 250.));
+    assert_eq!(scale.shift(50, 250., 500.), 100);
+    //dragging goes on past the top of the list and below zero
+    assert_eq!(scale.shift(100, 250., 500.), 150);
+    assert_eq!(scale.shift(10, -100., 500.), -10);
+
+    let scale = Scale::of(&[-25, 10, 75]);
+    assert_eq!(scale.bar(-25, 100.), (25., -25.));
+    assert_eq!(scale.bar(75, 100.), (25., 75.));
+    assert_eq!(scale.shift(75, -100., 100.), -25);
+    assert_eq!(scale.shift(i32::MAX, 100., 100.), i32::MAX);
+
+    //a list of zeros still gets a range
+    assert_eq!(Scale::of(&[0, 0]).bar(0, 100.), (0., 0.));
+}